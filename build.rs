@@ -0,0 +1,344 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use image::{
+    codecs::png::{CompressionType, FilterType, PngEncoder},
+    RgbaImage,
+};
+
+// itch page art that lives in res/ but isn't used in game
+const IGNORED: &[&str] = &["cover.png", "screenshot1.png"];
+
+// formats every audio backend can decode
+const SOUND_EXTENSIONS: &[&str] = &["mp3", "ogg", "wav"];
+
+// webgl2 (and wgpu's downlevel_webgl2_defaults) only promise 2048, anything that doesn't
+// fit spills onto another page
+const ATLAS_SIZE: u32 = 2048;
+// transparent gap between sprites so linear filtering doesn't bleed neighbours in
+const ATLAS_PADDING: u32 = 2;
+
+struct Sprite {
+    name: String,
+    path: PathBuf,
+    image: RgbaImage,
+    slice: Option<[u32; 4]>,
+    page: usize,
+    x: u32,
+    y: u32,
+}
+
+fn main() {
+    let res = Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("res");
+    println!("cargo:rerun-if-changed={}", res.display());
+
+    let mut sprites = Vec::new();
+    collect(&res, &res, &mut sprites);
+    sprites.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut seen: HashMap<&str, &Path> = HashMap::new();
+    for sprite in &sprites {
+        if let Some(other) = seen.insert(&sprite.name, &sprite.path) {
+            panic!(
+                "sprite name {} is used by both {} and {}",
+                sprite.name,
+                other.display(),
+                sprite.path.display()
+            );
+        }
+    }
+
+    let mut idents: HashMap<String, &str> = HashMap::new();
+    for sprite in &sprites {
+        if let Some(other) = idents.insert(ident(&sprite.name), &sprite.name) {
+            panic!(
+                "sprites {} and {} both become SpriteId::{}",
+                other,
+                sprite.name,
+                ident(&sprite.name)
+            );
+        }
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let pages = pack(&mut sprites);
+    let page_paths: Vec<PathBuf> = pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| {
+            let path = out_dir.join(format!("atlas{}.png", i));
+            save_page(page, &path);
+            path
+        })
+        .collect();
+
+    let mut out = String::new();
+    // not every texture is drawn, so unused variants are expected. data files like
+    // animations.json refer to sprites by their file name
+    out.push_str(
+        "#[allow(dead_code)]\n#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Deserialize)]\npub enum SpriteId {\n",
+    );
+    for sprite in &sprites {
+        out.push_str(&format!(
+            "    #[serde(rename = {:?})]\n    {},\n",
+            sprite.name,
+            ident(&sprite.name)
+        ));
+    }
+    out.push_str("}\n\npub const SPRITE_SIZES: &[(u32, u32)] = &[\n");
+    for sprite in &sprites {
+        out.push_str(&format!(
+            "    ({}, {}),\n",
+            sprite.image.width(),
+            sprite.image.height()
+        ));
+    }
+    out.push_str("];\n\npub const SPRITE_ATLAS: &[(usize, u32, u32)] = &[\n");
+    for sprite in &sprites {
+        out.push_str(&format!(
+            "    ({}, {}, {}),\n",
+            sprite.page, sprite.x, sprite.y
+        ));
+    }
+    out.push_str("];\n\npub const SPRITE_SLICES: &[Option<[u32; 4]>] = &[\n");
+    for sprite in &sprites {
+        out.push_str(&format!("    {:?},\n", sprite.slice));
+    }
+    out.push_str(&format!(
+        "];\n\npub const ATLAS_SIZE: u32 = {};\n\npub const ATLAS_PAGES: &[&[u8]] = &[\n",
+        ATLAS_SIZE
+    ));
+    for path in &page_paths {
+        out.push_str(&format!(
+            "    include_bytes!({:?}),\n",
+            path.display().to_string()
+        ));
+    }
+    out.push_str(
+        "];\n\n#[allow(dead_code, non_upper_case_globals)]\npub mod hovered {\n    use super::SpriteId;\n\n",
+    );
+    for sprite in &sprites {
+        if let Some(base) = sprite.name.strip_suffix("_hovered") {
+            out.push_str(&format!(
+                "    pub const {}: SpriteId = SpriteId::{};\n",
+                ident(base),
+                ident(&sprite.name)
+            ));
+        }
+    }
+    out.push_str("}\n");
+
+    fs::write(out_dir.join("sprites.rs"), out).unwrap();
+
+    write_sounds(&res.join("sounds"), &out_dir);
+}
+
+// res/sounds/note1.mp3 becomes SoundId::Note1, the files get embedded so playing never
+// waits on a fetch
+fn write_sounds(dir: &Path, out_dir: &Path) {
+    let mut sounds: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("failed reading {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| SOUND_EXTENSIONS.contains(&&*ext.to_string_lossy()))
+        })
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            (name, path)
+        })
+        .collect();
+    sounds.sort();
+
+    let mut idents: HashMap<String, &Path> = HashMap::new();
+    for (name, path) in &sounds {
+        if let Some(other) = idents.insert(ident(name), path) {
+            panic!(
+                "sounds {} and {} both become SoundId::{}",
+                other.display(),
+                path.display(),
+                ident(name)
+            );
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str(
+        "#[allow(dead_code)]\n#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Deserialize)]\npub enum SoundId {\n",
+    );
+    for (name, _) in &sounds {
+        out.push_str(&format!(
+            "    #[serde(rename = {:?})]\n    {},\n",
+            name,
+            ident(name)
+        ));
+    }
+    out.push_str("}\n\npub const SOUNDS: &[SoundId] = &[\n");
+    for (name, _) in &sounds {
+        out.push_str(&format!("    SoundId::{},\n", ident(name)));
+    }
+    out.push_str("];\n\npub const SOUND_FILES: &[&[u8]] = &[\n");
+    for (_, path) in &sounds {
+        out.push_str(&format!(
+            "    include_bytes!({:?}),\n",
+            path.display().to_string()
+        ));
+    }
+    out.push_str("];\n");
+
+    fs::write(out_dir.join("sounds.rs"), out).unwrap();
+}
+
+// res/front/garden_hovered.png becomes front_garden_hovered
+fn collect(root: &Path, dir: &Path, sprites: &mut Vec<Sprite>) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("failed reading {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect(root, &path, sprites);
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        if path.extension().map_or(true, |ext| ext != "png") || IGNORED.contains(&&*file_name) {
+            continue;
+        }
+
+        let name = path
+            .strip_prefix(root)
+            .unwrap()
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("_");
+
+        let image = image::open(&path)
+            .unwrap_or_else(|e| panic!("failed loading {}: {}", path.display(), e))
+            .to_rgba8();
+        if image.width() + ATLAS_PADDING > ATLAS_SIZE || image.height() + ATLAS_PADDING > ATLAS_SIZE
+        {
+            panic!(
+                "{} doesn't fit in a {}x{} atlas",
+                path.display(),
+                ATLAS_SIZE,
+                ATLAS_SIZE
+            );
+        }
+
+        let slice = read_slice(&path, &image);
+
+        sprites.push(Sprite {
+            name,
+            path,
+            image,
+            slice,
+            page: 0,
+            x: 0,
+            y: 0,
+        });
+    }
+}
+
+// res/ui/panel.slice next to res/ui/panel.png holds the nine-slice border insets in
+// pixels as "left top right bottom"
+fn read_slice(path: &Path, image: &RgbaImage) -> Option<[u32; 4]> {
+    let slice_path = path.with_extension("slice");
+    let text = fs::read_to_string(&slice_path).ok()?;
+    println!("cargo:rerun-if-changed={}", slice_path.display());
+
+    let insets: Vec<u32> = text
+        .split_whitespace()
+        .map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|e| panic!("bad inset in {}: {}", slice_path.display(), e))
+        })
+        .collect();
+    let [left, top, right, bottom] = insets[..] else {
+        panic!(
+            "{} should hold 4 insets, left top right bottom",
+            slice_path.display()
+        );
+    };
+    if left + right > image.width() || top + bottom > image.height() {
+        panic!(
+            "insets in {} are bigger than the sprite",
+            slice_path.display()
+        );
+    }
+
+    Some([left, top, right, bottom])
+}
+
+// shelf packing, tallest sprites first so shelves waste little height
+fn pack(sprites: &mut [Sprite]) -> Vec<RgbaImage> {
+    let mut order: Vec<usize> = (0..sprites.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sprites[i].image.height()));
+
+    let mut pages = vec![RgbaImage::new(ATLAS_SIZE, ATLAS_SIZE)];
+    let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+
+    for i in order {
+        let sprite = &mut sprites[i];
+        let (width, height) = (
+            sprite.image.width() + ATLAS_PADDING,
+            sprite.image.height() + ATLAS_PADDING,
+        );
+
+        if x + width > ATLAS_SIZE {
+            x = 0;
+            y += shelf_height;
+            shelf_height = 0;
+        }
+        if y + height > ATLAS_SIZE {
+            pages.push(RgbaImage::new(ATLAS_SIZE, ATLAS_SIZE));
+            x = 0;
+            y = 0;
+            shelf_height = 0;
+        }
+
+        sprite.page = pages.len() - 1;
+        sprite.x = x;
+        sprite.y = y;
+        image::imageops::replace(pages.last_mut().unwrap(), &sprite.image, x as i64, y as i64);
+
+        x += width;
+        shelf_height = shelf_height.max(height);
+    }
+
+    pages
+}
+
+// pages get embedded in the wasm binary, so spend the extra build time on compression
+fn save_page(page: &RgbaImage, path: &Path) {
+    let file = fs::File::create(path)
+        .unwrap_or_else(|e| panic!("failed creating {}: {}", path.display(), e));
+    let encoder = PngEncoder::new_with_quality(
+        std::io::BufWriter::new(file),
+        CompressionType::Best,
+        FilterType::Adaptive,
+    );
+    page.write_with_encoder(encoder)
+        .unwrap_or_else(|e| panic!("failed writing {}: {}", path.display(), e));
+}
+
+// front_garden_hovered becomes FrontGardenHovered
+fn ident(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
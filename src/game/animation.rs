@@ -0,0 +1,215 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use serde::Deserialize;
+
+use super::sprite_manifest::SpriteId;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    Loop,
+    Once,
+    PingPong,
+}
+
+// durations are in seconds, event is handed back from Animator::update when the frame starts
+#[derive(Debug, Deserialize)]
+pub struct AnimationFrame {
+    pub sprite: SpriteId,
+    pub duration: f32,
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnimationClip {
+    pub mode: PlayMode,
+    pub frames: Vec<AnimationFrame>,
+}
+
+fn clips() -> &'static HashMap<String, AnimationClip> {
+    static CLIPS: OnceLock<HashMap<String, AnimationClip>> = OnceLock::new();
+    CLIPS.get_or_init(|| {
+        let clips: HashMap<String, AnimationClip> =
+            serde_json::from_str(include_str!("../../res/animations.json"))
+                .unwrap_or_else(|e| panic!("failed parsing animations.json: {}", e));
+        for (name, clip) in clips.iter() {
+            if clip.frames.is_empty() || clip.frames.iter().any(|frame| frame.duration <= 0.0) {
+                panic!(
+                    "animation {} needs at least one frame and only positive durations",
+                    name
+                );
+            }
+        }
+        clips
+    })
+}
+
+pub fn clip(name: &str) -> &'static AnimationClip {
+    clips()
+        .get(name)
+        .unwrap_or_else(|| panic!("no animation named {}", name))
+}
+
+#[derive(Clone, Debug)]
+pub struct Animator {
+    clip: &'static AnimationClip,
+    frame: usize,
+    elapsed: f32,
+    backwards: bool,
+    started: bool,
+    finished: bool,
+}
+
+impl Animator {
+    pub fn new(name: &str) -> Self {
+        Self::from_clip(clip(name))
+    }
+
+    pub fn from_clip(clip: &'static AnimationClip) -> Self {
+        Self {
+            clip,
+            frame: 0,
+            elapsed: 0.0,
+            backwards: false,
+            started: false,
+            finished: false,
+        }
+    }
+
+    pub fn restart(&mut self) {
+        *self = Self::from_clip(self.clip);
+    }
+
+    pub fn sprite(&self) -> SpriteId {
+        self.clip.frames[self.frame].sprite
+    }
+
+    // only once clips ever finish, they hold their last frame afterwards
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // events of every frame started during dt, in order
+    pub fn update(&mut self, dt: f32) -> Vec<&'static str> {
+        let clip = self.clip;
+        let mut events = Vec::new();
+
+        if !self.started {
+            self.started = true;
+            events.extend(clip.frames[0].event.as_deref());
+        }
+        if self.finished {
+            return events;
+        }
+
+        let last = clip.frames.len() - 1;
+        self.elapsed += dt;
+        while self.elapsed >= clip.frames[self.frame].duration {
+            let next = match (clip.mode, self.backwards) {
+                (_, false) if self.frame < last => self.frame + 1,
+                (PlayMode::PingPong, true) if self.frame > 0 => self.frame - 1,
+                (PlayMode::Loop, _) => 0,
+                (PlayMode::Once, _) => {
+                    self.finished = true;
+                    break;
+                }
+                (PlayMode::PingPong, _) => {
+                    self.backwards = !self.backwards;
+                    match (last, self.backwards) {
+                        (0, _) => 0,
+                        (_, true) => self.frame - 1,
+                        (_, false) => self.frame + 1,
+                    }
+                }
+            };
+
+            self.elapsed -= clip.frames[self.frame].duration;
+            self.frame = next;
+            events.extend(clip.frames[next].event.as_deref());
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPRITES: [SpriteId; 3] = [SpriteId::HouseGuy, SpriteId::HouseGuy1, SpriteId::HouseGuy2];
+
+    // three quarter second frames, the sprites tell them apart
+    fn animator(mode: PlayMode, events: [Option<&str>; 3]) -> Animator {
+        let frames = SPRITES
+            .into_iter()
+            .zip(events)
+            .map(|(sprite, event)| AnimationFrame {
+                sprite,
+                duration: 0.25,
+                event: event.map(String::from),
+            })
+            .collect();
+        Animator::from_clip(Box::leak(Box::new(AnimationClip { mode, frames })))
+    }
+
+    // the frame shown after each of steps quarter seconds
+    fn play(animator: &mut Animator, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                animator.update(0.25);
+                SPRITES
+                    .iter()
+                    .position(|sprite| *sprite == animator.sprite())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let mut animator = animator(PlayMode::Loop, [None; 3]);
+        assert_eq!(play(&mut animator, 7), [1, 2, 0, 1, 2, 0, 1]);
+        assert!(!animator.is_finished());
+    }
+
+    #[test]
+    fn once_holds_the_last_frame() {
+        let mut animator = animator(PlayMode::Once, [None; 3]);
+        assert_eq!(play(&mut animator, 2), [1, 2]);
+        assert!(!animator.is_finished());
+        assert_eq!(play(&mut animator, 3), [2, 2, 2]);
+        assert!(animator.is_finished());
+
+        animator.restart();
+        assert_eq!(animator.sprite(), SPRITES[0]);
+        assert!(!animator.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_turns_at_both_ends() {
+        let mut animator = animator(PlayMode::PingPong, [None; 3]);
+        assert_eq!(play(&mut animator, 8), [1, 2, 1, 0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn events_fire_when_their_frame_starts() {
+        let mut animator = animator(PlayMode::Loop, [Some("first"), Some("second"), None]);
+        assert_eq!(animator.update(0.1), ["first"]);
+        assert!(animator.update(0.1).is_empty());
+        assert_eq!(animator.update(0.1), ["second"]);
+        // long steps hand back every frame they went through, in order
+        assert_eq!(animator.update(0.75), ["first", "second"]);
+    }
+
+    #[test]
+    fn house_guy_waves_both_ways() {
+        let mut animator = Animator::new("house_guy_wave");
+        let waves = (0..50)
+            .flat_map(|_| animator.update(0.01))
+            .filter(|event| *event == "wave")
+            .count();
+        // in half a second it reaches the waving frame at 0.1 and comes back through it at 0.46
+        assert_eq!(waves, 2);
+    }
+}
@@ -0,0 +1,646 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use super::{
+    mixer::{Bus, Mixer},
+    settings::GameSettings,
+    sound_manifest::{sound_bytes, SoundId, SOUNDS},
+    sound_pool::SoundVariation,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SoundHandle(u64);
+
+// backends only start, change and stop single sounds, handles and fades are kept by Audio.
+// every sound in the manifest is decoded once up front, playing one that isn't ready yet
+// does nothing
+pub trait AudioBackend {
+    // 0 to 1, how much of the manifest is decoded
+    fn load_progress(&self) -> f32;
+    // pitch is a playback rate, 2 is twice as fast and an octave up
+    fn play(&mut self, handle: SoundHandle, sound: SoundId, volume: f32, pitch: f32);
+    fn set_volume(&mut self, handle: SoundHandle, volume: f32);
+    fn stop(&mut self, handle: SoundHandle);
+    fn is_playing(&self, handle: SoundHandle) -> bool;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn default_audio() -> Box<dyn AudioBackend> {
+    match WebAudio::new() {
+        Some(audio) => Box::new(audio),
+        None => {
+            log::error!("web audio unavailable, the game will be silent");
+            Box::new(NullAudio)
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_audio() -> Box<dyn AudioBackend> {
+    match NativeAudio::new() {
+        Some(audio) => Box::new(audio),
+        None => {
+            log::error!("no audio output device, the game will be silent");
+            Box::new(NullAudio)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Fade {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+    stop_when_done: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Playing {
+    bus: Bus,
+    volume: f32,
+    fade: Option<Fade>,
+}
+
+// handles stay valid after their sound ends, using them then just does nothing. volumes
+// given here are before the bus gain
+pub struct Audio {
+    backend: Box<dyn AudioBackend>,
+    mixer: Mixer,
+    playing: HashMap<SoundHandle, Playing>,
+    next_handle: u64,
+}
+
+impl Audio {
+    pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+        Self {
+            backend,
+            mixer: Mixer::default(),
+            playing: HashMap::new(),
+            next_handle: 0,
+        }
+    }
+
+    pub fn play(&mut self, bus: Bus, sound: SoundId, volume: f32) -> SoundHandle {
+        self.start(bus, sound, volume, 1.0)
+    }
+
+    pub fn play_variation(&mut self, bus: Bus, variation: SoundVariation) -> SoundHandle {
+        self.start(bus, variation.sound, variation.volume, variation.pitch)
+    }
+
+    // sounds on a silent bus aren't started at all, their handle is never playing
+    fn start(&mut self, bus: Bus, sound: SoundId, volume: f32, pitch: f32) -> SoundHandle {
+        let handle = SoundHandle(self.next_handle);
+        self.next_handle += 1;
+
+        if self.mixer.is_silent(bus) {
+            return handle;
+        }
+        let volume = volume.max(0.0);
+        self.backend
+            .play(handle, sound, volume * self.mixer.gain(bus), pitch);
+        self.playing.insert(
+            handle,
+            Playing {
+                bus,
+                volume,
+                fade: None,
+            },
+        );
+        handle
+    }
+
+    pub fn load_progress(&self) -> f32 {
+        self.backend.load_progress()
+    }
+
+    pub fn apply_settings(&mut self, settings: &GameSettings) {
+        if self.mixer.apply_settings(settings) {
+            self.push_volumes(&Bus::ALL);
+        }
+    }
+
+    pub fn set_bus_volume(&mut self, bus: Bus, volume: f32) {
+        if self.mixer.set_volume(bus, volume) {
+            self.push_volumes(&[bus]);
+        }
+    }
+
+    pub fn set_bus_muted(&mut self, bus: Bus, muted: bool) {
+        if self.mixer.set_muted(bus, muted) {
+            self.push_volumes(&[bus]);
+        }
+    }
+
+    pub fn duck(&mut self, bus: Bus, depth: f32, hold: f32) {
+        self.mixer.duck(bus, depth, hold);
+    }
+
+    fn push_volumes(&mut self, buses: &[Bus]) {
+        for (handle, playing) in self.playing.iter() {
+            if buses.contains(&playing.bus) {
+                self.backend
+                    .set_volume(*handle, playing.volume * self.mixer.gain(playing.bus));
+            }
+        }
+    }
+
+    pub fn stop(&mut self, handle: SoundHandle) {
+        if self.playing.remove(&handle).is_some() {
+            self.backend.stop(handle);
+        }
+    }
+
+    pub fn stop_all(&mut self) {
+        for (handle, _) in self.playing.drain() {
+            self.backend.stop(handle);
+        }
+    }
+
+    pub fn is_playing(&self, handle: SoundHandle) -> bool {
+        self.playing.contains_key(&handle) && self.backend.is_playing(handle)
+    }
+
+    pub fn volume(&self, handle: SoundHandle) -> Option<f32> {
+        self.playing.get(&handle).map(|playing| playing.volume)
+    }
+
+    // cancels a running fade
+    pub fn set_volume(&mut self, handle: SoundHandle, volume: f32) {
+        if let Some(playing) = self.playing.get_mut(&handle) {
+            playing.volume = volume.max(0.0);
+            playing.fade = None;
+            self.backend
+                .set_volume(handle, playing.volume * self.mixer.gain(playing.bus));
+        }
+    }
+
+    pub fn fade_to(&mut self, handle: SoundHandle, volume: f32, duration: f32) {
+        self.start_fade(handle, volume.max(0.0), duration, false);
+    }
+
+    // the sound is stopped once it's silent
+    pub fn fade_out(&mut self, handle: SoundHandle, duration: f32) {
+        self.start_fade(handle, 0.0, duration, true);
+    }
+
+    fn start_fade(&mut self, handle: SoundHandle, to: f32, duration: f32, stop_when_done: bool) {
+        if let Some(playing) = self.playing.get_mut(&handle) {
+            playing.fade = Some(Fade {
+                from: playing.volume,
+                to,
+                duration,
+                elapsed: 0.0,
+                stop_when_done,
+            });
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let ducked = self.mixer.update(dt);
+        let Self {
+            backend,
+            mixer,
+            playing,
+            ..
+        } = self;
+
+        playing.retain(|handle, playing| {
+            if !backend.is_playing(*handle) {
+                // lets the backend free whatever the finished sound held on to
+                backend.stop(*handle);
+                return false;
+            }
+
+            let Some(fade) = &mut playing.fade else {
+                if ducked.contains(&playing.bus) {
+                    backend.set_volume(*handle, playing.volume * mixer.gain(playing.bus));
+                }
+                return true;
+            };
+            fade.elapsed += dt;
+            let t = if fade.duration > 0.0 {
+                (fade.elapsed / fade.duration).min(1.0)
+            } else {
+                1.0
+            };
+            playing.volume = fade.from + (fade.to - fade.from) * t;
+            backend.set_volume(*handle, playing.volume * mixer.gain(playing.bus));
+
+            if t < 1.0 {
+                return true;
+            }
+            let stop = fade.stop_when_done;
+            playing.fade = None;
+            if stop {
+                backend.stop(*handle);
+            }
+            !stop
+        });
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+struct WebVoice {
+    source: web_sys::AudioBufferSourceNode,
+    gain: web_sys::GainNode,
+    ends_at: f64,
+}
+
+// decodes the embedded files into AudioBuffers in the background, each play is a buffer
+// source through its own gain node
+#[cfg(target_arch = "wasm32")]
+pub struct WebAudio {
+    context: web_sys::AudioContext,
+    buffers: Rc<RefCell<Vec<Option<web_sys::AudioBuffer>>>>,
+    // decodes that finished, failed ones included so loading still completes
+    decoded: Rc<std::cell::Cell<usize>>,
+    voices: HashMap<SoundHandle, WebVoice>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebAudio {
+    pub fn new() -> Option<Self> {
+        let context = web_sys::AudioContext::new().ok()?;
+        let buffers = Rc::new(RefCell::new(vec![None; SOUNDS.len()]));
+        let decoded = Rc::new(std::cell::Cell::new(0));
+
+        for &sound in SOUNDS {
+            let context = context.clone();
+            let buffers = buffers.clone();
+            let decoded = decoded.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match decode(&context, sound_bytes(sound)).await {
+                    Ok(buffer) => buffers.borrow_mut()[sound as usize] = Some(buffer),
+                    Err(e) => log::error!("failed decoding {:?}: {:?}", sound, e),
+                }
+                decoded.set(decoded.get() + 1);
+            });
+        }
+
+        Some(Self {
+            context,
+            buffers,
+            decoded,
+            voices: HashMap::new(),
+        })
+    }
+
+    fn start(
+        &self,
+        buffer: &web_sys::AudioBuffer,
+        volume: f32,
+        pitch: f32,
+    ) -> Result<WebVoice, wasm_bindgen::JsValue> {
+        let source = self.context.create_buffer_source()?;
+        source.set_buffer(Some(buffer));
+        source.playback_rate().set_value(pitch);
+        let gain = self.context.create_gain()?;
+        gain.gain().set_value(volume);
+        source.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&self.context.destination())?;
+        source.start()?;
+        Ok(WebVoice {
+            source,
+            gain,
+            ends_at: self.context.current_time() + buffer.duration() / pitch as f64,
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn decode(
+    context: &web_sys::AudioContext,
+    bytes: &[u8],
+) -> Result<web_sys::AudioBuffer, wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsCast;
+
+    // decodeAudioData takes the buffer over, so it gets a copy of the embedded bytes
+    let array = js_sys::Uint8Array::from(bytes);
+    let promise = context.decode_audio_data(&array.buffer())?;
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await?
+        .dyn_into()
+}
+
+#[cfg(target_arch = "wasm32")]
+impl AudioBackend for WebAudio {
+    fn load_progress(&self) -> f32 {
+        self.decoded.get() as f32 / SOUNDS.len().max(1) as f32
+    }
+
+    fn play(&mut self, handle: SoundHandle, sound: SoundId, volume: f32, pitch: f32) {
+        // browsers keep the context suspended until the page gets a user gesture, sounds
+        // asked for before that are dropped instead of all playing at once later
+        if self.context.state() != web_sys::AudioContextState::Running {
+            let _ = self.context.resume();
+            return;
+        }
+
+        let Some(buffer) = self.buffers.borrow()[sound as usize].clone() else {
+            return;
+        };
+        match self.start(&buffer, volume, pitch) {
+            Ok(voice) => {
+                self.voices.insert(handle, voice);
+            }
+            Err(e) => log::error!("failed playing {:?}: {:?}", sound, e),
+        }
+    }
+
+    fn set_volume(&mut self, handle: SoundHandle, volume: f32) {
+        if let Some(voice) = self.voices.get(&handle) {
+            voice.gain.gain().set_value(volume);
+        }
+    }
+
+    fn stop(&mut self, handle: SoundHandle) {
+        if let Some(voice) = self.voices.remove(&handle) {
+            let _ = voice.source.stop();
+            let _ = voice.gain.disconnect();
+        }
+    }
+
+    fn is_playing(&self, handle: SoundHandle) -> bool {
+        self.voices
+            .get(&handle)
+            .is_some_and(|voice| self.context.current_time() < voice.ends_at)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct DecodedSound {
+    channels: u16,
+    sample_rate: u32,
+    samples: Vec<i16>,
+}
+
+// everything is decoded to samples when created, so plays never touch the decoder
+#[cfg(not(target_arch = "wasm32"))]
+pub struct NativeAudio {
+    _stream: rodio::OutputStream,
+    stream_handle: rodio::OutputStreamHandle,
+    sounds: Vec<Option<DecodedSound>>,
+    sinks: HashMap<SoundHandle, rodio::Sink>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NativeAudio {
+    pub fn new() -> Option<Self> {
+        use rodio::Source;
+
+        let (stream, stream_handle) = rodio::OutputStream::try_default().ok()?;
+        let sounds = SOUNDS
+            .iter()
+            .map(
+                |&sound| match rodio::Decoder::new(std::io::Cursor::new(sound_bytes(sound))) {
+                    Ok(decoder) => Some(DecodedSound {
+                        channels: decoder.channels(),
+                        sample_rate: decoder.sample_rate(),
+                        samples: decoder.collect(),
+                    }),
+                    Err(e) => {
+                        log::error!("failed decoding {:?}: {}", sound, e);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Some(Self {
+            _stream: stream,
+            stream_handle,
+            sounds,
+            sinks: HashMap::new(),
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AudioBackend for NativeAudio {
+    fn load_progress(&self) -> f32 {
+        1.0
+    }
+
+    fn play(&mut self, handle: SoundHandle, sound: SoundId, volume: f32, pitch: f32) {
+        use rodio::Source;
+
+        let Some(decoded) = &self.sounds[sound as usize] else {
+            return;
+        };
+        match rodio::Sink::try_new(&self.stream_handle) {
+            Ok(sink) => {
+                sink.set_volume(volume);
+                sink.append(
+                    rodio::buffer::SamplesBuffer::new(
+                        decoded.channels,
+                        decoded.sample_rate,
+                        decoded.samples.clone(),
+                    )
+                    .speed(pitch),
+                );
+                self.sinks.insert(handle, sink);
+            }
+            Err(e) => log::error!("failed playing {:?}: {}", sound, e),
+        }
+    }
+
+    fn set_volume(&mut self, handle: SoundHandle, volume: f32) {
+        if let Some(sink) = self.sinks.get(&handle) {
+            sink.set_volume(volume);
+        }
+    }
+
+    fn stop(&mut self, handle: SoundHandle) {
+        if let Some(sink) = self.sinks.remove(&handle) {
+            sink.stop();
+        }
+    }
+
+    fn is_playing(&self, handle: SoundHandle) -> bool {
+        self.sinks.get(&handle).is_some_and(|sink| !sink.empty())
+    }
+}
+
+// nothing ever plays, for headless runs without an output device
+pub struct NullAudio;
+
+impl AudioBackend for NullAudio {
+    fn load_progress(&self) -> f32 {
+        1.0
+    }
+
+    fn play(&mut self, _handle: SoundHandle, _sound: SoundId, _volume: f32, _pitch: f32) {}
+    fn set_volume(&mut self, _handle: SoundHandle, _volume: f32) {}
+    fn stop(&mut self, _handle: SoundHandle) {}
+    fn is_playing(&self, _handle: SoundHandle) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum AudioEvent {
+    Play {
+        handle: SoundHandle,
+        sound: SoundId,
+        volume: f32,
+        pitch: f32,
+    },
+    SetVolume {
+        handle: SoundHandle,
+        volume: f32,
+    },
+    Stop(SoundHandle),
+}
+
+// keeps everything asked of it so tests can check what was played, sounds never end on
+// their own. grab events() before boxing it up for Audio
+#[derive(Default)]
+pub struct RecordingAudio {
+    events: Rc<RefCell<Vec<AudioEvent>>>,
+    playing: HashSet<SoundHandle>,
+}
+
+impl RecordingAudio {
+    pub fn events(&self) -> Rc<RefCell<Vec<AudioEvent>>> {
+        self.events.clone()
+    }
+}
+
+impl AudioBackend for RecordingAudio {
+    fn load_progress(&self) -> f32 {
+        1.0
+    }
+
+    fn play(&mut self, handle: SoundHandle, sound: SoundId, volume: f32, pitch: f32) {
+        self.playing.insert(handle);
+        self.events.borrow_mut().push(AudioEvent::Play {
+            handle,
+            sound,
+            volume,
+            pitch,
+        });
+    }
+
+    fn set_volume(&mut self, handle: SoundHandle, volume: f32) {
+        self.events
+            .borrow_mut()
+            .push(AudioEvent::SetVolume { handle, volume });
+    }
+
+    fn stop(&mut self, handle: SoundHandle) {
+        if self.playing.remove(&handle) {
+            self.events.borrow_mut().push(AudioEvent::Stop(handle));
+        }
+    }
+
+    fn is_playing(&self, handle: SoundHandle) -> bool {
+        self.playing.contains(&handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording() -> (Audio, Rc<RefCell<Vec<AudioEvent>>>) {
+        let backend = RecordingAudio::default();
+        let events = backend.events();
+        (Audio::new(Box::new(backend)), events)
+    }
+
+    #[test]
+    fn play_set_volume_and_stop_reach_the_backend() {
+        let (mut audio, events) = recording();
+        let handle = audio.play(Bus::Sfx, SoundId::Door, 0.5);
+        assert!(audio.is_playing(handle));
+        audio.set_volume(handle, 0.25);
+        audio.stop(handle);
+        assert!(!audio.is_playing(handle));
+        // stopping twice is fine and doesn't reach the backend again
+        audio.stop(handle);
+
+        assert_eq!(
+            *events.borrow(),
+            [
+                AudioEvent::Play {
+                    handle,
+                    sound: SoundId::Door,
+                    volume: 0.5,
+                    pitch: 1.0,
+                },
+                AudioEvent::SetVolume {
+                    handle,
+                    volume: 0.25,
+                },
+                AudioEvent::Stop(handle),
+            ]
+        );
+    }
+
+    #[test]
+    fn fade_out_ramps_down_and_ends_in_stop() {
+        let (mut audio, events) = recording();
+        let handle = audio.play(Bus::Music, SoundId::Note1, 1.0);
+        audio.fade_out(handle, 1.0);
+        audio.update(0.5);
+        assert!(audio.is_playing(handle));
+        audio.update(0.5);
+        assert!(!audio.is_playing(handle));
+        audio.update(0.5);
+
+        assert_eq!(
+            events.borrow()[1..],
+            [
+                AudioEvent::SetVolume {
+                    handle,
+                    volume: 0.5,
+                },
+                AudioEvent::SetVolume {
+                    handle,
+                    volume: 0.0,
+                },
+                AudioEvent::Stop(handle),
+            ]
+        );
+    }
+
+    #[test]
+    fn silent_buses_never_start_sounds() {
+        let (mut audio, events) = recording();
+        audio.set_bus_muted(Bus::Sfx, true);
+        audio.set_bus_volume(Bus::Music, 0.0);
+
+        let muted = audio.play(Bus::Sfx, SoundId::Pot1, 1.0);
+        let quiet = audio.play(Bus::Music, SoundId::Note2, 1.0);
+        audio.fade_to(muted, 0.5, 1.0);
+        audio.update(1.0);
+
+        assert!(!audio.is_playing(muted));
+        assert!(!audio.is_playing(quiet));
+        assert!(events.borrow().is_empty());
+
+        // other buses are untouched
+        let playing = audio.play(Bus::Ambience, SoundId::Noise, 1.0);
+        assert!(audio.is_playing(playing));
+    }
+
+    #[test]
+    fn set_volume_cancels_a_fade() {
+        let (mut audio, events) = recording();
+        let handle = audio.play(Bus::Sfx, SoundId::Leaf, 1.0);
+        audio.fade_out(handle, 1.0);
+        audio.update(0.25);
+        audio.set_volume(handle, 0.6);
+        events.borrow_mut().clear();
+
+        audio.update(1.0);
+        assert!(audio.is_playing(handle));
+        assert_eq!(audio.volume(handle), Some(0.6));
+        assert!(events.borrow().is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use jandering_engine::types::Vec2;
+
+use super::tween::{Easing, Tween};
+
+const MAX_SHAKE_OFFSET: f32 = 24.0;
+const MAX_SHAKE_ROTATION: f32 = 0.04;
+// trauma lost per second
+const TRAUMA_DECAY: f32 = 1.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CameraView {
+    pub position: Vec2,
+    pub zoom: f32,
+    pub rotation: f32,
+}
+
+impl Default for CameraView {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl CameraView {
+    // screen is in game resolution pixels from the middle of the screen, y up, which is
+    // what the engine's unmoved camera gives back for the mouse
+    pub fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        Vec2::from_angle(self.rotation).rotate(screen / self.zoom) + self.position
+    }
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            position: self.position.lerp(other.position, t),
+            zoom: self.zoom + (other.zoom - self.zoom) * t,
+            rotation: self.rotation + (other.rotation - self.rotation) * t,
+        }
+    }
+}
+
+// scenes pan, zoom and add trauma, shake grows with trauma squared so small hits stay subtle
+pub struct Camera {
+    pan: Tween<Vec2>,
+    zoom: Tween<f32>,
+    trauma: f32,
+    time: f32,
+    previous: CameraView,
+    current: CameraView,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            pan: Tween::new(Vec2::ZERO, Vec2::ZERO, 0.0),
+            zoom: Tween::new(1.0, 1.0, 0.0),
+            trauma: 0.0,
+            time: 0.0,
+            previous: CameraView::default(),
+            current: CameraView::default(),
+        }
+    }
+}
+
+impl Camera {
+    pub fn pan_to(&mut self, position: Vec2, duration: f32) {
+        self.pan = Tween::new(self.pan.value(), position, duration).with_easing(Easing::QuadInOut);
+    }
+
+    pub fn zoom_to(&mut self, zoom: f32, duration: f32) {
+        self.zoom = Tween::new(self.zoom.value(), zoom, duration).with_easing(Easing::QuadInOut);
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    // snaps straight back, for cuts between scenes
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn update(&mut self, dt: f32, reduced_motion: bool) {
+        self.previous = self.current;
+        self.time += dt;
+        self.pan.update(dt);
+        self.zoom.update(dt);
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+
+        // a few detuned sines instead of the rng, so shaking never changes a run
+        let shake = if reduced_motion {
+            0.0
+        } else {
+            self.trauma * self.trauma
+        };
+        let t = self.time;
+        let offset = Vec2::new(
+            (t * 53.0).sin() + (t * 31.0 + 1.3).sin() * 0.5,
+            (t * 47.0 + 0.7).sin() + (t * 29.0 + 2.1).sin() * 0.5,
+        ) / 1.5
+            * MAX_SHAKE_OFFSET
+            * shake;
+        let rotation = (t * 41.0 + 0.4).sin() * MAX_SHAKE_ROTATION * shake;
+
+        self.current = CameraView {
+            position: self.pan.value() + offset,
+            zoom: self.zoom.value(),
+            rotation,
+        };
+    }
+
+    pub fn view(&self, alpha: f32) -> CameraView {
+        self.previous.lerp(self.current, alpha)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // what sprite_shader.wgsl does to a world position before dividing by the resolution
+    fn world_to_screen(view: &CameraView, world: Vec2) -> Vec2 {
+        let relative = world - view.position;
+        let (s, c) = view.rotation.sin_cos();
+        Vec2::new(
+            relative.x * c + relative.y * s,
+            -relative.x * s + relative.y * c,
+        ) * view.zoom
+    }
+
+    #[test]
+    fn screen_to_world_undoes_the_render_transform() {
+        let mut camera = Camera::default();
+        camera.pan_to(Vec2::new(120.0, -40.0), 0.5);
+        camera.zoom_to(1.6, 0.5);
+        camera.add_trauma(1.0);
+        for _ in 0..20 {
+            camera.update(1.0 / 120.0, false);
+        }
+
+        let view = camera.view(0.3);
+        assert!(view.rotation != 0.0 && view.zoom != 1.0);
+        for screen in [
+            Vec2::ZERO,
+            Vec2::new(200.0, 150.0),
+            Vec2::new(-310.0, 90.0),
+            Vec2::new(45.0, -240.0),
+        ] {
+            let back = world_to_screen(&view, view.screen_to_world(screen));
+            assert!(
+                back.distance(screen) < 1e-3,
+                "{} came back as {}",
+                screen,
+                back
+            );
+        }
+    }
+}
@@ -1,28 +1,35 @@
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 
 use super::{
-    constants::FRAME_LENGTH, scenes::ActiveScene, sounds::play_sound,
-    sprite_renderer::SpriteRenderer, GameData,
+    animation::Animator,
+    constants::TEXT_LAYER,
+    mixer::Bus,
+    scenes::ActiveScene,
+    sound_pool::SoundPool,
+    sprite_manifest::{sprite_size, SpriteId},
+    sprite_renderer::{measure_text, SpriteEffects, SpriteRenderer, TextAlign, TextStyle},
+    tween::{Sequence, Tweenable},
+    GameData,
 };
 
+// space between a label and the edge of its panel
+const LABEL_PADDING: Vec2 = Vec2::new(14.0, 8.0);
+const HOVERED_LABEL_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+
 #[derive(Copy, Clone)]
 pub enum ObjectAction {
     Goto(ActiveScene),
+    NewGame(usize),
+    LoadSlot(usize),
     NewDay,
     Pressed,
     Exit,
 }
 
-#[derive(Clone)]
-pub struct ObjectFrame {
-    pub tex: &'static str,
-    pub frames: u32,
-}
-
 #[derive(Clone)]
 pub enum ObjectSprite {
-    Frame(&'static str),
-    Frames(Vec<ObjectFrame>),
+    Frame(SpriteId),
+    Animation(Animator),
 }
 
 #[derive(Clone)]
@@ -37,22 +44,28 @@ pub struct ClickableObject {
     pub rotation: f32,
     pub texture: ObjectSprite,
     pub hovered_texture: ObjectSprite,
-    pub hovered_sounds: Option<Vec<&'static str>>,
-    time: f32,
+    pub hovered_sounds: Option<SoundPool>,
+    pub effects: SpriteEffects,
+    // finished tweens are dropped, the last value they wrote stays
+    pub position_tween: Option<Sequence<Vec2>>,
+    pub scale_tween: Option<Sequence<f32>>,
+    pub rotation_tween: Option<Sequence<f32>>,
+    // events from animation frames and tweens during the last update
+    pub events: Vec<&'static str>,
+    // position, scale and rotation from before the last update stepped the tweens. None
+    // when nothing was tweening, so positions set from outside never get smeared
+    previous: Option<(Vec2, f32, f32)>,
+    nine_slice_size: Option<Vec2>,
+    label: Option<(String, TextStyle)>,
 }
 
 impl ClickableObject {
-    pub fn new(
-        position: Vec2,
-        texture: ObjectSprite,
-        hovered_texture: ObjectSprite,
-        sprite_renderer: &mut SpriteRenderer,
-    ) -> Self {
+    pub fn new(position: Vec2, texture: ObjectSprite, hovered_texture: ObjectSprite) -> Self {
         let first_tex = match &texture {
-            ObjectSprite::Frame(tex) => tex,
-            ObjectSprite::Frames(vec) => vec[0].tex,
+            ObjectSprite::Frame(tex) => *tex,
+            ObjectSprite::Animation(animator) => animator.sprite(),
         };
-        let size = sprite_renderer.get_sprite(first_tex).size;
+        let size = sprite_size(first_tex);
         Self {
             texture,
             size,
@@ -62,29 +75,50 @@ impl ClickableObject {
             is_hovered: false,
             is_held: false,
             z_index: 1,
-            time: 0.0,
             scale: 1.0,
             rotation: 0.0,
             hovered_sounds: None,
+            effects: SpriteEffects::default(),
+            position_tween: None,
+            scale_tween: None,
+            rotation_tween: None,
+            events: Vec::new(),
+            previous: None,
+            nine_slice_size: None,
+            label: None,
         }
     }
 
-    pub fn update(&mut self, context: &EngineContext, data: &mut GameData) {
+    // a nine-slice panel fitted around its text, centred on position
+    pub fn with_label(position: Vec2, panel: SpriteId, text: &str, style: TextStyle) -> Self {
+        let style = TextStyle {
+            align: TextAlign::Center,
+            ..style
+        };
+        let mut object = Self::new(
+            position,
+            ObjectSprite::Frame(panel),
+            ObjectSprite::Frame(panel),
+        );
+        object.set_nine_slice_size(measure_text(text, &style) + LABEL_PADDING * 2.0);
+        object.label = Some((text.to_string(), style));
+        object
+    }
+
+    pub fn update(&mut self, dt: f32, data: &mut GameData) {
+        let was_hovered = self.is_hovered;
         if let Some(mouse_pos) = data.input.mouse_pos {
             let hovered = self.is_hovered(mouse_pos);
-            if data.settings.sound_on {
-                if let Some(hovered_sounds) = &mut self.hovered_sounds {
-                    if !self.is_hovered && hovered {
-                        let last = hovered_sounds.pop().unwrap();
-                        play_sound(last, 0.75);
-                        hovered_sounds.insert(0, last);
-                    }
+            if let Some(hovered_sounds) = &mut self.hovered_sounds {
+                if !self.is_hovered && hovered {
+                    let sound = hovered_sounds.pick(data.effects_rng);
+                    data.audio.play_variation(Bus::Sfx, sound);
+                    data.audio.duck(Bus::Ambience, 0.6, 0.3);
                 }
             }
             self.is_hovered = hovered;
         }
         self.is_clicked = false;
-        self.time += context.dt as f32;
         if data.input.left_pressed && self.is_hovered {
             self.is_clicked = true;
             self.is_held = true;
@@ -93,7 +127,25 @@ impl ClickableObject {
             self.is_held = false;
         }
 
-        self.update_sprite();
+        self.events.clear();
+        self.update_animation(dt, was_hovered != self.is_hovered);
+        let is_tweening = self.position_tween.is_some()
+            || self.scale_tween.is_some()
+            || self.rotation_tween.is_some();
+        self.previous = is_tweening.then_some((self.position, self.scale, self.rotation));
+        step_tween(
+            &mut self.position_tween,
+            &mut self.position,
+            dt,
+            &mut self.events,
+        );
+        step_tween(&mut self.scale_tween, &mut self.scale, dt, &mut self.events);
+        step_tween(
+            &mut self.rotation_tween,
+            &mut self.rotation,
+            dt,
+            &mut self.events,
+        );
     }
 
     pub fn is_hovered(&self, mouse_pos: Vec2) -> bool {
@@ -106,33 +158,80 @@ impl ClickableObject {
     }
 
     pub fn render(&self, sprite_renderer: &mut SpriteRenderer) {
-        let texture_handle = self.get_current_frame();
-
-        sprite_renderer.render_with_scale(
-            D2Instance {
-                position: self.position,
-                rotation: self.rotation,
-                ..Default::default()
-            },
-            texture_handle,
-            1,
-            self.scale,
-        )
+        self.render_at(sprite_renderer, self.position);
     }
 
     pub fn render_at(&self, sprite_renderer: &mut SpriteRenderer, position: Vec2) {
-        let texture_handle = self.get_current_frame();
-
-        sprite_renderer.render_with_scale(
-            D2Instance {
-                position,
-                rotation: self.rotation,
-                ..Default::default()
-            },
-            texture_handle,
-            1,
-            self.scale,
-        )
+        self.draw(sprite_renderer, position, self.scale, self.rotation);
+    }
+
+    // for tweened objects, draws them between the previous and the current tick like the
+    // minigames do with what they move. animation frames still switch on ticks
+    pub fn render_interpolated(&self, sprite_renderer: &mut SpriteRenderer, alpha: f32) {
+        match self.previous {
+            Some((_, scale, rotation)) => self.draw(
+                sprite_renderer,
+                self.interpolated_position(alpha),
+                scale.lerp(self.scale, alpha),
+                rotation.lerp(self.rotation, alpha),
+            ),
+            None => self.render(sprite_renderer),
+        }
+    }
+
+    // where render_interpolated draws it, for putting things next to it
+    pub fn interpolated_position(&self, alpha: f32) -> Vec2 {
+        match self.previous {
+            Some((position, _, _)) => position.lerp(self.position, alpha),
+            None => self.position,
+        }
+    }
+
+    fn draw(
+        &self,
+        sprite_renderer: &mut SpriteRenderer,
+        position: Vec2,
+        scale: f32,
+        rotation: f32,
+    ) {
+        let instance = D2Instance {
+            position,
+            rotation,
+            ..Default::default()
+        };
+        let sprite = self.get_current_frame();
+        match self.nine_slice_size {
+            Some(size) => {
+                sprite_renderer.render_nine_slice(instance, sprite, 1, size * scale, self.effects)
+            }
+            None => sprite_renderer.render_with_effects(instance, sprite, 1, scale, self.effects),
+        }
+
+        if let Some((text, style)) = &self.label {
+            let top = self.size.y * 0.5 - LABEL_PADDING.y;
+            let style = TextStyle {
+                size: style.size * scale,
+                color: if self.is_hovered {
+                    HOVERED_LABEL_COLOR
+                } else {
+                    style.color
+                },
+                ..*style
+            };
+            sprite_renderer.render_text(
+                text,
+                position + Vec2::new(0.0, top * scale),
+                &style,
+                TEXT_LAYER,
+            );
+        }
+    }
+
+    // draws the sprite as a nine-slice panel of this size instead, hovering and size() go
+    // by it too
+    pub fn set_nine_slice_size(&mut self, size: Vec2) {
+        self.nine_slice_size = Some(size);
+        self.size = size;
     }
 
     fn get_current_tex(&self) -> &ObjectSprite {
@@ -143,101 +242,129 @@ impl ClickableObject {
         }
     }
 
-    pub fn get_current_frame(&self) -> &str {
+    pub fn get_current_frame(&self) -> SpriteId {
         match self.get_current_tex() {
-            ObjectSprite::Frame(tex) => tex,
-            ObjectSprite::Frames(vec) => {
-                let mut acc = 0.0;
-                for frame in vec.iter() {
-                    acc += frame.frames as f32 * FRAME_LENGTH;
-                    if acc > self.time {
-                        return frame.tex;
-                    }
-                }
-                vec.last().unwrap().tex
-            }
+            ObjectSprite::Frame(tex) => *tex,
+            ObjectSprite::Animation(animator) => animator.sprite(),
         }
     }
 
-    fn update_sprite(&mut self) {
-        if let ObjectSprite::Frames(vec) = self.get_current_tex() {
-            let length = vec
-                .iter()
-                .fold(0.0, |acc, e| acc + e.frames as f32 * FRAME_LENGTH);
-            self.time %= length;
+    // switching between the idle and hovered texture plays the new one from the start
+    fn update_animation(&mut self, dt: f32, restart: bool) {
+        let texture = if self.is_hovered {
+            &mut self.hovered_texture
+        } else {
+            &mut self.texture
+        };
+        if let ObjectSprite::Animation(animator) = texture {
+            if restart {
+                animator.restart();
+            }
+            self.events.extend(animator.update(dt));
         }
     }
 
-    pub fn size(&self, sprite_renderer: &mut SpriteRenderer) -> Vec2 {
-        sprite_renderer.get_sprite(self.get_current_frame()).size * self.scale
+    fn unscaled_size(&self) -> Vec2 {
+        self.nine_slice_size
+            .unwrap_or_else(|| sprite_size(self.get_current_frame()))
     }
 
-    pub fn swap_textures(
-        &mut self,
-        tex: ObjectSprite,
-        hovered: ObjectSprite,
-        sprite_renderer: &mut SpriteRenderer,
-    ) {
+    pub fn size(&self) -> Vec2 {
+        self.unscaled_size() * self.scale
+    }
+
+    pub fn swap_textures(&mut self, tex: ObjectSprite, hovered: ObjectSprite) {
         self.texture = tex;
         self.hovered_texture = hovered;
-        self.size = self.size(sprite_renderer);
+        self.size = self.unscaled_size();
+    }
+}
+
+fn step_tween<T: Tweenable>(
+    tween: &mut Option<Sequence<T>>,
+    value: &mut T,
+    dt: f32,
+    events: &mut Vec<&'static str>,
+) {
+    if let Some(sequence) = tween {
+        events.extend(sequence.update(dt));
+        *value = sequence.value();
+        if sequence.is_finished() {
+            *tween = None;
+        }
     }
 }
 
 #[macro_export]
 macro_rules! clickable {
-    ($x: expr, $y: expr, $tex: expr, $sprite_renderer: expr) => {
+    ($x: expr, $y: expr, $tex: ident) => {
         ClickableObject::new(
             Vec2::new($x, $y),
-            ObjectSprite::Frame($tex),
-            ObjectSprite::Frame(concat!($tex, "_hovered")),
-            $sprite_renderer,
+            ObjectSprite::Frame($crate::game::sprite_manifest::SpriteId::$tex),
+            ObjectSprite::Frame($crate::game::sprite_manifest::hovered::$tex),
         )
     };
 }
 
 #[macro_export]
 macro_rules! clickable_nohover {
-    ($x: expr, $y: expr, $tex: expr, $sprite_renderer: expr) => {
+    ($x: expr, $y: expr, $tex: expr) => {
         ClickableObject::new(
             Vec2::new($x, $y),
             ObjectSprite::Frame($tex),
             ObjectSprite::Frame($tex),
-            $sprite_renderer,
         )
     };
 }
 
+// plays the same clip whether hovered or not
 #[macro_export]
 macro_rules! clickable_idleanim {
-    ($x: expr, $y: expr, $sprite_renderer: expr $(, $frame: expr)+ ) => {
+    ($x: expr, $y: expr, $clip: expr) => {
         ClickableObject::new(
             Vec2::new($x, $y),
-            ObjectSprite::Frames(0.0, vec![$($frame)+]),
-            "test",
-            $sprite_renderer,
+            ObjectSprite::Animation($crate::game::animation::Animator::new($clip)),
+            ObjectSprite::Animation($crate::game::animation::Animator::new($clip)),
         )
     };
 }
 
 #[macro_export]
 macro_rules! clickable_fullanim {
-    ($x: expr, $y: expr, $sprite_renderer: expr, $first_frame: expr, $(, $frame: expr)+ ) => {
+    ($x: expr, $y: expr, $clip: expr, $hovered_clip: expr) => {
         ClickableObject::new(
             Vec2::new($x, $y),
-            ObjectSprite::Frames(0.0, vec![$first_frame, $($frame)+]),
-            "test",
-            $sprite_renderer,
+            ObjectSprite::Animation($crate::game::animation::Animator::new($clip)),
+            ObjectSprite::Animation($crate::game::animation::Animator::new($hovered_clip)),
         )
     };
 }
 
-#[macro_export]
-macro_rules! frame {
-    ($tex: expr, $len: expr) => {
-        ObjectFrame {
-            tex: $tex,
-            frames: $len,
-        }
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_size_their_panel_and_hover_area() {
+        let style = TextStyle::default();
+        let mut button =
+            ClickableObject::with_label(Vec2::ZERO, SpriteId::UiPanel, "a long label", style);
+        let text_size = measure_text("a long label", &style);
+        assert_eq!(button.size(), text_size + LABEL_PADDING * 2.0);
+
+        let edge = Vec2::new(text_size.x * 0.5 + LABEL_PADDING.x - 1.0, 0.0);
+        assert!(button.is_hovered(edge));
+        assert!(!button.is_hovered(edge + Vec2::new(2.0, 0.0)));
+
+        button.scale = 2.0;
+        assert_eq!(button.size(), (text_size + LABEL_PADDING * 2.0) * 2.0);
+        assert!(button.is_hovered(edge * 2.0));
+
+        // swapping textures keeps the panel size instead of falling back to the sprite's
+        button.swap_textures(
+            ObjectSprite::Frame(SpriteId::UiPanel),
+            ObjectSprite::Frame(SpriteId::UiPanel),
+        );
+        assert_eq!(button.size(), (text_size + LABEL_PADDING * 2.0) * 2.0);
+    }
 }
@@ -3,7 +3,11 @@ pub const RESOLUTION_Y: u32 = 675;
 
 pub const SLEEP_LENGTH: f32 = 2.0;
 
-pub const FRAME_LENGTH: f32 = 1.0 / 60.0;
+pub const TICK_LENGTH: f32 = 1.0 / 120.0;
+pub const MAX_TICKS_PER_FRAME: u32 = 8;
+
+// a recording is written out this often, so a closed tab or a crash loses at most this much
+pub const REPLAY_STORE_TICKS: usize = (10.0 / TICK_LENGTH) as usize;
 
 pub const STARTING_CASH: u32 = 3;
 pub const STARTING_POTS: u32 = 1;
@@ -14,4 +18,9 @@ pub const FLOWER_PRICE: u32 = 2;
 pub const STRAWBERRY_PRICE: u32 = 6;
 pub const WATERMELON_PRICE: u32 = 15;
 
+// above every scene's sprites, below full screen overlays
+pub const TEXT_LAYER: u32 = 500;
+
+pub const SAVE_SLOTS: usize = 3;
+
 pub const SKIP_INTRO: bool = false;
@@ -0,0 +1,23 @@
+// options the game is started with, ?name=value in the url on the web
+// and GARDENERE_NAME=value natively
+#[cfg(target_arch = "wasm32")]
+pub fn option(name: &str) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| match pair.split_once('=') {
+            Some((key, value)) if key == name => Some(value.to_string()),
+            None if pair == name => Some(String::new()),
+            _ => None,
+        })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn option(name: &str) -> Option<String> {
+    std::env::var(format!("GARDENERE_{}", name.to_uppercase())).ok()
+}
+
+pub fn flag(name: &str) -> bool {
+    option(name).is_some()
+}
@@ -2,12 +2,14 @@ use crate::{
     clickable_nohover,
     game::{
         clickableobject::{ClickableObject, ObjectSprite},
+        sprite_manifest::SpriteId,
         sprite_renderer::SpriteRenderer,
     },
 };
 use jandering_engine::{object::D2Instance, types::Vec2};
+use serde::{Deserialize, Serialize};
 
-use super::{player::Player, post_processing::PostProcessing};
+use super::{player::Player, post_processing::PostProcessingState};
 
 const BLOOD_POS: Vec2 = Vec2::new(-90.0, 0.0);
 
@@ -18,6 +20,7 @@ pub struct MainPlant {
     pub requires_blood: bool,
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum MainPlantStage {
     Planted,
     Second,
@@ -29,9 +32,24 @@ pub enum MainPlantStage {
     Final,
 }
 
+impl MainPlantStage {
+    pub fn sprite(&self) -> SpriteId {
+        match self {
+            MainPlantStage::Planted => SpriteId::MainplantGrowth0,
+            MainPlantStage::Second => SpriteId::MainplantGrowth1,
+            MainPlantStage::Third => SpriteId::MainplantGrowth2,
+            MainPlantStage::Blood => SpriteId::MainplantGrowth3,
+            MainPlantStage::Scary => SpriteId::MainplantGrowth4,
+            MainPlantStage::Overgrown => SpriteId::MainplantGrowth5,
+            MainPlantStage::Gone => SpriteId::Empty,
+            MainPlantStage::Final => SpriteId::MainplantGrowth6,
+        }
+    }
+}
+
 impl MainPlant {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let object = clickable_nohover!(0.0, 0.0, "mainplant_growth0", sprite_renderer);
+    pub fn new() -> Self {
+        let object = clickable_nohover!(0.0, 0.0, SpriteId::MainplantGrowth0);
         Self {
             growth: 0,
             object,
@@ -47,7 +65,7 @@ impl MainPlant {
                 position: Vec2::new(-10.0, -170.0),
                 ..Default::default()
             },
-            "garden_pot",
+            SpriteId::GardenPot,
             3,
         );
 
@@ -59,13 +77,13 @@ impl MainPlant {
                     position: BLOOD_POS,
                     ..Default::default()
                 },
-                "mainplant_blood",
+                SpriteId::MainplantBlood,
                 4,
             );
         }
     }
 
-    pub fn new_day(&mut self, player: &mut Player, popr: &mut PostProcessing) {
+    pub fn new_day(&mut self, player: &mut Player, popr: &mut PostProcessingState) {
         self.growth += if self.requires_blood { 100 } else { 1 };
         let next_stage = match self.stage {
             MainPlantStage::Planted => {
@@ -125,23 +143,17 @@ impl MainPlant {
         };
         if let Some(stage) = next_stage {
             self.growth = 0;
-            self.stage = stage;
-            let (tex, hovered) = match self.stage {
-                MainPlantStage::Planted => ("mainplant_growth0", None),
-                MainPlantStage::Second => ("mainplant_growth1", None),
-                MainPlantStage::Third => ("mainplant_growth2", None),
-                MainPlantStage::Blood => ("mainplant_growth3", None),
-                MainPlantStage::Scary => ("mainplant_growth4", None),
-                MainPlantStage::Overgrown => ("mainplant_growth5", None),
-                MainPlantStage::Gone => ("empty", None),
-                MainPlantStage::Final => ("mainplant_growth6", None),
-            };
-            self.object.texture = ObjectSprite::Frame(tex);
-            self.object.hovered_texture = hovered.unwrap_or(self.object.texture.clone());
+            self.set_stage(stage);
         }
     }
 
-    pub fn feed(&mut self, player: &mut Player, popr: &mut PostProcessing) {
+    pub fn set_stage(&mut self, stage: MainPlantStage) {
+        self.stage = stage;
+        self.object.texture = ObjectSprite::Frame(stage.sprite());
+        self.object.hovered_texture = self.object.texture.clone();
+    }
+
+    pub fn feed(&mut self, player: &mut Player, popr: &mut PostProcessingState) {
         if self.requires_blood {
             if player.cut_finger && !player.used_finger {
                 player.used_finger = true;
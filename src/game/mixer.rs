@@ -0,0 +1,273 @@
+use super::settings::GameSettings;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Bus {
+    // the distortion noise
+    Ambience,
+    // the random notes
+    Music,
+    // ui and everything set off by the player
+    Sfx,
+}
+
+impl Bus {
+    pub const ALL: [Bus; 3] = [Bus::Ambience, Bus::Music, Bus::Sfx];
+}
+
+// attenuation gained and lost per second while ducking
+const DUCK_ATTACK: f32 = 8.0;
+const DUCK_RELEASE: f32 = 1.5;
+
+#[derive(Clone, Copy, Debug)]
+struct BusState {
+    volume: f32,
+    muted: bool,
+    duck: f32,
+    duck_target: f32,
+    duck_hold: f32,
+}
+
+impl Default for BusState {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+            duck: 0.0,
+            duck_target: 0.0,
+            duck_hold: 0.0,
+        }
+    }
+}
+
+// the volume a sound ends up at is its own times its bus gain
+#[derive(Clone, Debug)]
+pub struct Mixer {
+    master: f32,
+    buses: [BusState; 3],
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            buses: [BusState::default(); 3],
+        }
+    }
+}
+
+impl Mixer {
+    fn bus(&self, bus: Bus) -> &BusState {
+        &self.buses[bus as usize]
+    }
+
+    fn bus_mut(&mut self, bus: Bus) -> &mut BusState {
+        &mut self.buses[bus as usize]
+    }
+
+    pub fn gain(&self, bus: Bus) -> f32 {
+        let state = self.bus(bus);
+        if state.muted {
+            0.0
+        } else {
+            self.master * state.volume * (1.0 - state.duck)
+        }
+    }
+
+    // ducking doesn't count, a ducked bus still starts new sounds
+    pub fn is_silent(&self, bus: Bus) -> bool {
+        let state = self.bus(bus);
+        state.muted || self.master * state.volume <= 0.0
+    }
+
+    // these return whether anything changed, so volumes only get pushed out when needed
+    pub fn set_master_volume(&mut self, volume: f32) -> bool {
+        let volume = volume.max(0.0);
+        let changed = self.master != volume;
+        self.master = volume;
+        changed
+    }
+
+    pub fn set_volume(&mut self, bus: Bus, volume: f32) -> bool {
+        let volume = volume.max(0.0);
+        let state = self.bus_mut(bus);
+        let changed = state.volume != volume;
+        state.volume = volume;
+        changed
+    }
+
+    pub fn set_muted(&mut self, bus: Bus, muted: bool) -> bool {
+        let state = self.bus_mut(bus);
+        let changed = state.muted != muted;
+        state.muted = muted;
+        changed
+    }
+
+    pub fn apply_settings(&mut self, settings: &GameSettings) -> bool {
+        let master = if settings.sound_on {
+            settings.master_volume
+        } else {
+            0.0
+        };
+        let mut changed = self.set_master_volume(master);
+        for bus in Bus::ALL {
+            changed |= self.set_volume(bus, settings.bus_volume(bus));
+            changed |= self.set_muted(bus, settings.bus_muted(bus));
+        }
+        changed
+    }
+
+    // lowers the bus by depth (0 to 1) for at least hold seconds, overlapping ducks keep
+    // the deepest one
+    pub fn duck(&mut self, bus: Bus, depth: f32, hold: f32) {
+        let state = self.bus_mut(bus);
+        state.duck_target = state.duck_target.max(depth.clamp(0.0, 1.0));
+        state.duck_hold = state.duck_hold.max(hold);
+    }
+
+    // returns the buses whose gain moved
+    pub fn update(&mut self, dt: f32) -> Vec<Bus> {
+        let mut changed = Vec::new();
+        for bus in Bus::ALL {
+            let state = self.bus_mut(bus);
+            state.duck_hold -= dt;
+            if state.duck_hold <= 0.0 {
+                state.duck_hold = 0.0;
+                state.duck_target = 0.0;
+            }
+
+            let duck = if state.duck < state.duck_target {
+                (state.duck + DUCK_ATTACK * dt).min(state.duck_target)
+            } else {
+                (state.duck - DUCK_RELEASE * dt).max(state.duck_target)
+            };
+            if duck != state.duck {
+                state.duck = duck;
+                changed.push(bus);
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        audio::{Audio, AudioEvent, RecordingAudio},
+        sound_manifest::SoundId,
+    };
+
+    // a power of two so every step lands exactly
+    const DT: f32 = 1.0 / 64.0;
+
+    fn step(mixer: &mut Mixer, steps: usize) -> Vec<Bus> {
+        let mut changed = Vec::new();
+        for _ in 0..steps {
+            changed = mixer.update(DT);
+        }
+        changed
+    }
+
+    #[test]
+    fn ducks_attack_hold_and_release() {
+        let mut mixer = Mixer::default();
+        mixer.duck(Bus::Sfx, 0.5, 0.25);
+
+        // attack takes 0.125 off per step
+        assert_eq!(step(&mut mixer, 1), [Bus::Sfx]);
+        assert_eq!(mixer.gain(Bus::Sfx), 0.875);
+        step(&mut mixer, 3);
+        assert_eq!(mixer.gain(Bus::Sfx), 0.5);
+
+        // held at depth until the 16th step, when the hold runs out
+        assert!(step(&mut mixer, 11).is_empty());
+        assert_eq!(mixer.gain(Bus::Sfx), 0.5);
+        assert_eq!(step(&mut mixer, 1), [Bus::Sfx]);
+        assert!(mixer.gain(Bus::Sfx) > 0.5);
+
+        // release is slower, 0.5 / (1.5 / 64) rounds up to 22 steps
+        step(&mut mixer, 20);
+        assert!(mixer.gain(Bus::Sfx) < 1.0);
+        step(&mut mixer, 1);
+        assert_eq!(mixer.gain(Bus::Sfx), 1.0);
+        assert!(step(&mut mixer, 1).is_empty());
+
+        assert_eq!(mixer.gain(Bus::Music), 1.0);
+        assert_eq!(mixer.gain(Bus::Ambience), 1.0);
+    }
+
+    #[test]
+    fn overlapping_ducks_keep_the_deepest_and_longest() {
+        let mut mixer = Mixer::default();
+        mixer.duck(Bus::Music, 0.25, 1.0);
+        mixer.duck(Bus::Music, 0.75, 0.1);
+        step(&mut mixer, 32);
+        assert_eq!(mixer.gain(Bus::Music), 0.25);
+        // ducking doesn't keep new sounds from starting
+        assert!(!mixer.is_silent(Bus::Music));
+    }
+
+    #[test]
+    fn settings_mute_and_scale_the_buses() {
+        let mut mixer = Mixer::default();
+        assert!(!mixer.apply_settings(&GameSettings::default()));
+
+        let settings = GameSettings {
+            master_volume: 0.5,
+            music_volume: 0.5,
+            sfx_muted: true,
+            ..Default::default()
+        };
+        assert!(mixer.apply_settings(&settings));
+        assert!(!mixer.apply_settings(&settings));
+        assert_eq!(mixer.gain(Bus::Music), 0.25);
+        assert_eq!(mixer.gain(Bus::Ambience), 0.5);
+        assert_eq!(mixer.gain(Bus::Sfx), 0.0);
+        assert!(mixer.is_silent(Bus::Sfx));
+
+        let sound_off = GameSettings {
+            sound_on: false,
+            ..settings
+        };
+        assert!(mixer.apply_settings(&sound_off));
+        for bus in Bus::ALL {
+            assert_eq!(mixer.gain(bus), 0.0);
+            assert!(mixer.is_silent(bus));
+        }
+    }
+
+    #[test]
+    fn bus_gain_changes_reach_playing_sounds() {
+        let backend = RecordingAudio::default();
+        let events = backend.events();
+        let mut audio = Audio::new(Box::new(backend));
+        let music = audio.play(Bus::Music, SoundId::Note1, 0.5);
+        let sfx = audio.play(Bus::Sfx, SoundId::Door, 1.0);
+        events.borrow_mut().clear();
+
+        audio.duck(Bus::Music, 0.5, 1.0);
+        audio.update(DT);
+        assert_eq!(
+            *events.borrow(),
+            [AudioEvent::SetVolume {
+                handle: music,
+                volume: 0.4375,
+            }]
+        );
+        events.borrow_mut().clear();
+
+        audio.apply_settings(&GameSettings {
+            sound_on: false,
+            ..Default::default()
+        });
+        // the order follows the map of playing sounds, so only what got pushed counts
+        let events = events.borrow();
+        assert_eq!(events.len(), 2);
+        for handle in [music, sfx] {
+            assert!(events.contains(&AudioEvent::SetVolume {
+                handle,
+                volume: 0.0,
+            }));
+        }
+    }
+}
@@ -1,37 +1,53 @@
+pub mod animation;
+pub mod audio;
+pub mod camera;
 pub mod clickableobject;
 mod constants;
+mod launch;
 mod main_plant;
+pub mod mixer;
+pub mod music;
+mod particles;
 mod plant;
 mod player;
 mod polygon;
 pub mod post_processing;
+mod replay;
+mod rng;
+mod save;
 mod scenes;
-mod sounds;
+mod settings;
+mod simulation;
+mod sound_manifest;
+pub mod sound_pool;
+mod sprite_manifest;
 pub mod sprite_renderer;
-
-use std::collections::HashMap;
+mod storage;
+pub mod tween;
 
 use jandering_engine::engine::EngineContext;
-use jandering_engine::object::D2Instance;
 use jandering_engine::types::{Vec2, Vec3};
 use jandering_engine::{
     bind_group::camera::d2::D2CameraBindGroup, engine::Engine, renderer::BindGroupHandle,
 };
-use rand::Rng;
 use wgpu::Color;
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{ElementState, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
 
 use jandering_engine::{engine::EngineDescriptor, renderer::Renderer};
 
-use constants::{RESOLUTION_X, RESOLUTION_Y};
+use constants::{MAX_TICKS_PER_FRAME, RESOLUTION_X, RESOLUTION_Y, TEXT_LAYER, TICK_LENGTH};
 
-use self::constants::{STARTING_CASH, STARTING_POTS};
+use self::audio::Audio;
+use self::camera::Camera;
 use self::main_plant::MainPlant;
 use self::player::Player;
-use self::post_processing::PostProcessing;
-use self::scenes::{ActiveScene, Scenes};
-use self::sounds::play_sound;
+use self::post_processing::{PostProcessing, PostProcessingState};
+use self::replay::{Playback, Replay};
+use self::rng::GameRng;
+use self::settings::GameSettings;
+use self::simulation::Simulation;
 use self::sprite_renderer::SpriteRenderer;
+use self::storage::{MemoryStorage, Storage};
 
 const CLEAR_COLOR: Color = wgpu::Color {
     r: 0.7,
@@ -44,24 +60,29 @@ pub struct Game {
     engine: Engine,
     camera_bg: BindGroupHandle<D2CameraBindGroup>,
     sprite_renderer: SpriteRenderer,
-    scenes: Scenes,
-    popr: PostProcessing,
-    player: Player,
-    main_plant: MainPlant,
-    settings: GameSettings,
-    rng: rand::rngs::ThreadRng,
+    post_processing: PostProcessing,
+    simulation: Simulation,
+    playback: Option<Playback>,
 }
 
+#[derive(Default)]
 pub struct InputInfo {
     left_pressed: bool,
     left_released: bool,
     right_pressed: bool,
     right_released: bool,
+    // mouse_screen comes from the window, mouse_pos is it moved through the camera each tick
+    mouse_screen: Option<Vec2>,
     mouse_pos: Option<Vec2>,
 }
 
-pub struct GameSettings {
-    sound_on: bool,
+impl InputInfo {
+    fn clear_presses(&mut self) {
+        self.left_pressed = false;
+        self.left_released = false;
+        self.right_pressed = false;
+        self.right_released = false;
+    }
 }
 
 pub struct GameData<'a> {
@@ -69,42 +90,70 @@ pub struct GameData<'a> {
     main_plant: &'a mut MainPlant,
     input: &'a mut InputInfo,
     settings: &'a mut GameSettings,
-    popr: &'a mut PostProcessing,
-    rng: &'a mut rand::rngs::ThreadRng,
+    popr: &'a mut PostProcessingState,
+    storage: &'a mut dyn Storage,
+    rng: &'a mut GameRng,
+    effects_rng: &'a mut GameRng,
+    audio: &'a mut Audio,
+    camera: &'a mut Camera,
+    // how far rendering is between the previous and the current tick
+    alpha: f32,
 }
 
 impl Game {
     pub async fn new() -> Self {
-        let rng = rand::thread_rng();
-
         let mut engine = Engine::new(EngineDescriptor {
             resolution: (RESOLUTION_X, RESOLUTION_Y),
             clear_color: Vec3::new(0.7, 0.6, 0.5),
             show_cursor: false,
         });
 
-        let popr = PostProcessing::new(&mut engine.renderer).await;
+        let post_processing = PostProcessing::new(&mut engine.renderer).await;
 
         let camera_bg: BindGroupHandle<D2CameraBindGroup> = engine
             .renderer
             .add_bind_group(D2CameraBindGroup::new(&engine.renderer, false));
 
-        let mut sprite_renderer = SpriteRenderer::new(&mut engine.renderer, camera_bg).await;
+        let mut sprite_renderer = SpriteRenderer::new(&mut engine.renderer).await;
+        // glyphs of one string overlap a little, y-sorting them would shuffle lines around
+        sprite_renderer.set_layer_y_sort(TEXT_LAYER, false);
 
-        let settings = GameSettings { sound_on: true };
+        let mut storage = storage::default_storage();
+        save::adopt_legacy_save(storage.as_mut());
+        let replay = if launch::flag("replay") {
+            Replay::fetch(storage.as_ref())
+        } else {
+            None
+        };
 
-        let (scenes, player, main_plant) = make_everything(&mut sprite_renderer);
+        // replays run against their own copy of the slots so real saves are never touched
+        let (simulation, playback) = match replay {
+            Some(replay) => {
+                let mut replay_storage = Box::<MemoryStorage>::default();
+                replay.restore_slots(replay_storage.as_mut());
+                let simulation =
+                    Simulation::new(replay_storage, audio::default_audio(), replay.seed, None);
+                (simulation, Some(Playback::new(replay)))
+            }
+            None => {
+                let seed = rng::requested_seed().unwrap_or_else(rng::random_seed);
+                let recording = if launch::flag("record") {
+                    Some(Replay::new(seed, storage.as_ref()))
+                } else {
+                    None
+                };
+                let audio = audio::default_audio();
+                (Simulation::new(storage, audio, seed, recording), None)
+            }
+        };
 
         Self {
             engine,
             camera_bg,
             sprite_renderer,
-            scenes,
-            popr,
-            player,
-            main_plant,
-            settings,
-            rng,
+            post_processing,
+            simulation,
+            playback,
         }
     }
 
@@ -113,132 +162,91 @@ impl Game {
             engine,
             camera_bg,
             mut sprite_renderer,
-            mut scenes,
-            mut popr,
-            mut player,
-            mut main_plant,
-            mut settings,
-            mut rng,
-            ..
+            mut post_processing,
+            mut simulation,
+            mut playback,
         } = self;
 
-        let mut input = InputInfo {
-            left_pressed: false,
-            left_released: false,
-            right_pressed: false,
-            right_released: false,
-            mouse_pos: None,
-        };
-
-        let mut refresh_scene = false;
-
-        let mut next_note = 1.0;
-        let mut next_noise = 0.5;
+        let mut fullscreen_requested = false;
+        let mut accumulator = 0.0;
+        let log_render_stats = launch::flag("render_stats");
+        let mut next_stats_log = 0.0;
 
         engine.run(move |context, renderer: &mut Renderer| {
-            update_input(context, &mut input);
-
-            for event in context.events {
-                if let WindowEvent::CursorMoved { position, .. } = event {
-                    let camera_bind_group = renderer.get_bind_group_t(camera_bg).unwrap();
-                    input.mouse_pos = Some(
-                        camera_bind_group
-                            .mouse_to_world(Vec2::new(position.x as f32, position.y as f32)),
-                    );
+            // long frames drop time instead of running an ever growing number of ticks
+            accumulator += (context.dt as f32).min(TICK_LENGTH * MAX_TICKS_PER_FRAME as f32);
+
+            if playback.is_none() {
+                update_input(context, &mut simulation.input);
+
+                for event in context.events {
+                    match event {
+                        WindowEvent::CursorMoved { position, .. } => {
+                            let camera_bind_group = renderer.get_bind_group_t(camera_bg).unwrap();
+                            simulation.input.mouse_screen =
+                                Some(camera_bind_group.mouse_to_world(Vec2::new(
+                                    position.x as f32,
+                                    position.y as f32,
+                                )));
+                        }
+                        // hiding the page shows up as lost focus
+                        WindowEvent::Focused(false) | WindowEvent::CloseRequested => {
+                            simulation.store_recording();
+                        }
+                        WindowEvent::KeyboardInput {
+                            input:
+                                KeyboardInput {
+                                    state: ElementState::Pressed,
+                                    virtual_keycode: Some(VirtualKeyCode::F9),
+                                    ..
+                                },
+                            ..
+                        } => simulation.export_recording(),
+                        _ => {}
+                    }
                 }
-            }
-
-            let mut data = GameData {
-                player: &mut player,
-                main_plant: &mut main_plant,
-                input: &mut input,
-                settings: &mut settings,
-                popr: &mut popr,
-                rng: &mut rng,
-            };
-
-            if refresh_scene {
-                scenes
-                    .get_active_scene()
-                    .refresh(&mut data, &mut sprite_renderer);
-                refresh_scene = false;
-            }
-
-            if next_noise < 0.0 && data.settings.sound_on {
-                play_sound(
-                    "res/sounds/noise.mp3",
-                    (data.popr.distortion as f64 + 1.0)
-                        .log(100.0)
-                        .clamp(0.0, 1.0),
-                );
-                next_noise = 0.5;
-            }
-            next_noise -= context.dt as f32;
 
-            if next_note < 0.0 && data.settings.sound_on {
-                if data.rng.gen::<f32>() * 3.0 > data.popr.distortion {
-                    play_random_high_note(&mut data);
-                } else {
-                    play_random_low_note(&mut data);
+                if simulation.input.left_pressed && !fullscreen_requested {
+                    simulation.settings.request_fullscreen();
+                    fullscreen_requested = true;
                 }
-                next_note = (1.0 + data.rng.gen::<f32>()) * 6.0;
             }
-            next_note -= context.dt as f32;
-
-            let scene = scenes.get_active_scene();
 
-            let action = scene.update(context, &mut sprite_renderer, &mut data);
+            while accumulator >= TICK_LENGTH {
+                let replayed_dt = playback
+                    .as_mut()
+                    .and_then(|playback| playback.next(&mut simulation.input));
+                if replayed_dt.is_none() && playback.take().is_some() {
+                    log::info!("replay finished, back to live input");
+                }
 
-            renderer.clear_texture(context.encoder, data.popr.target_texture, CLEAR_COLOR);
-            renderer.set_render_target(data.popr.target_texture);
+                simulation.update(replayed_dt.unwrap_or(TICK_LENGTH));
+                accumulator -= TICK_LENGTH;
+            }
 
-            scene.render(&mut data, &mut sprite_renderer);
+            renderer.clear_texture(context.encoder, post_processing.target_texture, CLEAR_COLOR);
+            renderer.set_render_target(post_processing.target_texture);
 
-            if data.player.cut_eye {
-                sprite_renderer.render(D2Instance::default(), "noeye", 1000);
-                data.popr.distortion = data.popr.distortion.max(3.0);
-            }
+            simulation.render(&mut sprite_renderer, accumulator / TICK_LENGTH);
 
             sprite_renderer.submit(context, renderer);
 
-            data.popr.render_tonemap(renderer, context);
-
-            if let Some(action) = action {
-                match action {
-                    clickableobject::ObjectAction::Goto(scene) => {
-                        if data.settings.sound_on {
-                            play_sound("res/sounds/leaf.mp3", 0.2 + data.rng.gen::<f64>() * 0.5);
-                        }
-                        if matches!(scene, ActiveScene::Title) {
-                            let (new_scenes, new_player, new_main_plant) =
-                                make_everything(&mut sprite_renderer);
-                            scenes = new_scenes;
-                            player = new_player;
-                            main_plant = new_main_plant;
-                        }
-                        scenes.set_scene(scene);
-                        refresh_scene = true;
-                    }
-                    clickableobject::ObjectAction::Exit => todo!(),
-                    clickableobject::ObjectAction::NewDay => {
-                        data.player.coins += 1;
-                        data.player.total_coins += 1;
-                        scenes.garden.new_day(&mut data, &mut sprite_renderer);
-                        (0..1).for_each(|_| data.main_plant.new_day(data.player, data.popr));
-                    }
-                    _ => {}
+            if log_render_stats {
+                next_stats_log -= context.dt as f32;
+                if next_stats_log < 0.0 {
+                    log::info!("{:?}", sprite_renderer.stats());
+                    next_stats_log = 1.0;
                 }
             }
+
+            post_processing.render_tonemap(&mut simulation.popr, renderer, context);
         });
     }
 }
 
+// presses only get set here, they're cleared once a tick has seen them so frames
+// that run no ticks don't lose clicks
 fn update_input(context: &EngineContext, input: &mut InputInfo) {
-    input.left_pressed = false;
-    input.left_released = false;
-    input.right_pressed = false;
-    input.right_released = false;
-
     context.events.iter().for_each(|e| {
         if let WindowEvent::MouseInput { state, button, .. } = e {
             if matches!(state, ElementState::Pressed) {
@@ -255,45 +263,3 @@ fn update_input(context: &EngineContext, input: &mut InputInfo) {
         }
     });
 }
-
-fn make_everything(sprite_renderer: &mut SpriteRenderer) -> (Scenes, Player, MainPlant) {
-    let scenes = Scenes::new(sprite_renderer);
-
-    let player = Player {
-        hp: 100.0,
-        coins: STARTING_CASH,
-        total_coins: STARTING_CASH,
-        owned_seeds: HashMap::new(),
-        owned_pots: STARTING_POTS,
-        has_axe: false,
-        cut_finger: false,
-        used_finger: false,
-        cut_eye: false,
-        used_eye: false,
-        has_page: false,
-    };
-
-    let main_plant = MainPlant::new(sprite_renderer);
-
-    (scenes, player, main_plant)
-}
-
-fn play_random_high_note(data: &mut GameData) {
-    let rand = data.rng.gen::<u32>() % 3;
-    let sound = match rand {
-        0 => "res/sounds/note1.mp3",
-        1 => "res/sounds/note2.mp3",
-        _ => "res/sounds/note3.mp3",
-    };
-    play_sound(sound, 0.3);
-}
-
-fn play_random_low_note(data: &mut GameData) {
-    let rand = data.rng.gen::<u32>() % 3;
-    let sound = match rand {
-        0 => "res/sounds/note4.mp3",
-        1 => "res/sounds/note5.mp3",
-        _ => "res/sounds/note6.mp3",
-    };
-    play_sound(sound, 0.3);
-}
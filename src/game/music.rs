@@ -0,0 +1,233 @@
+use rand::Rng;
+
+use super::{
+    main_plant::MainPlantStage,
+    mixer::Bus,
+    rng::{self, GameRng},
+    sound_manifest::SoundId,
+    sound_pool::{SoundPool, SoundVariation},
+};
+
+// the most notes stacked on one beat
+const MAX_VOICES: usize = 3;
+const NOISE_INTERVAL: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mood {
+    Calm,
+    Playful,
+    Dread,
+    Silent,
+}
+
+struct MoodStyle {
+    tempo: f32,
+    // chance of notes on any beat
+    density: f32,
+    // added on top of what the plant stage allows
+    extra_voices: usize,
+    low_only: bool,
+}
+
+impl Mood {
+    fn style(self) -> MoodStyle {
+        match self {
+            Mood::Calm => MoodStyle {
+                tempo: 60.0,
+                density: 0.11,
+                extra_voices: 0,
+                low_only: false,
+            },
+            Mood::Playful => MoodStyle {
+                tempo: 90.0,
+                density: 0.25,
+                extra_voices: 0,
+                low_only: false,
+            },
+            Mood::Dread => MoodStyle {
+                tempo: 40.0,
+                density: 0.3,
+                extra_voices: 1,
+                low_only: true,
+            },
+            Mood::Silent => MoodStyle {
+                tempo: 60.0,
+                density: 0.0,
+                extra_voices: 0,
+                low_only: false,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct MusicState {
+    pub mood: Mood,
+    pub stage: MainPlantStage,
+    pub distortion: f32,
+}
+
+// time is in seconds since the music started
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NoteEvent {
+    pub time: f32,
+    pub bus: Bus,
+    pub sound: SoundVariation,
+}
+
+// only decides what to play and when, the events are handed to Audio by the caller. it has
+// its own rng so the soundscape never changes a run
+pub struct Music {
+    rng: GameRng,
+    high_notes: SoundPool,
+    low_notes: SoundPool,
+    time: f32,
+    next_beat: f32,
+    next_noise: f32,
+}
+
+impl Music {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: rng::music(seed),
+            high_notes: SoundPool::new("high_notes"),
+            low_notes: SoundPool::new("low_notes"),
+            time: 0.0,
+            next_beat: 1.0,
+            next_noise: NOISE_INTERVAL,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, state: &MusicState) -> Vec<NoteEvent> {
+        self.time += dt;
+        let mut events = Vec::new();
+
+        while self.next_noise <= self.time {
+            events.push(NoteEvent {
+                time: self.next_noise,
+                bus: Bus::Ambience,
+                sound: SoundVariation {
+                    sound: SoundId::Noise,
+                    volume: (state.distortion + 1.0).log(100.0).clamp(0.0, 1.0),
+                    pitch: 1.0,
+                },
+            });
+            self.next_noise += NOISE_INTERVAL;
+        }
+
+        let style = state.mood.style();
+        while self.next_beat <= self.time {
+            let time = self.next_beat;
+            self.beat(time, state, &style, &mut events);
+            self.next_beat += 60.0 / style.tempo;
+        }
+
+        events
+    }
+
+    // the same events update would give over duration, stepped at a fixed rate
+    pub fn timeline(&mut self, duration: f32, step: f32, state: &MusicState) -> Vec<NoteEvent> {
+        let mut events = Vec::new();
+        let mut elapsed = 0.0;
+        while elapsed < duration {
+            events.extend(self.update(step, state));
+            elapsed += step;
+        }
+        events
+    }
+
+    fn beat(
+        &mut self,
+        time: f32,
+        state: &MusicState,
+        style: &MoodStyle,
+        events: &mut Vec<NoteEvent>,
+    ) {
+        if self.rng.gen::<f32>() >= style.density {
+            return;
+        }
+
+        // the further the plant is along and the stronger the distortion, the lower it gets
+        let stage = state.stage as u32 as f32;
+        let high_chance = 1.0 - state.distortion / 3.0 - stage * 0.08;
+        let scale = if !style.low_only && self.rng.gen::<f32>() < high_chance {
+            &mut self.high_notes
+        } else {
+            &mut self.low_notes
+        };
+
+        let max_voices = match state.stage {
+            MainPlantStage::Planted | MainPlantStage::Second => 1,
+            MainPlantStage::Third | MainPlantStage::Blood => 2,
+            _ => 3,
+        } + style.extra_voices;
+        let voices = self.rng.gen_range(1..=max_voices.min(MAX_VOICES));
+
+        // the pools avoid repeats, so stacked notes rarely double up. they share the
+        // volume of one so chords don't get louder
+        for _ in 0..voices {
+            let mut sound = scale.pick(&mut self.rng);
+            sound.volume /= (voices as f32).sqrt();
+            events.push(NoteEvent {
+                time,
+                bus: Bus::Music,
+                sound,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(mood: Mood) -> MusicState {
+        MusicState {
+            mood,
+            stage: MainPlantStage::Scary,
+            distortion: 0.5,
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_timeline() {
+        let timeline = |seed| Music::new(seed).timeline(60.0, 1.0 / 120.0, &state(Mood::Playful));
+        let notes = timeline(3);
+        assert!(notes.iter().any(|event| event.bus == Bus::Music));
+        assert_eq!(notes, timeline(3));
+        assert_ne!(notes, timeline(4));
+    }
+
+    #[test]
+    fn notes_come_from_their_own_stream() {
+        let mut music = rng::music(3);
+        let mut run = rng::seeded(3);
+        let mut effects = rng::effects(3);
+        let first: [u32; 3] = [music.gen(), run.gen(), effects.gen()];
+        assert_ne!(first[0], first[1]);
+        assert_ne!(first[0], first[2]);
+    }
+
+    #[test]
+    fn silent_mood_only_plays_noise() {
+        let events = Music::new(3).timeline(60.0, 1.0 / 120.0, &state(Mood::Silent));
+        assert!(!events.is_empty());
+        assert!(events
+            .iter()
+            .all(|event| event.bus == Bus::Ambience && event.sound.sound == SoundId::Noise));
+    }
+
+    #[test]
+    fn dread_only_plays_low_notes() {
+        let events = Music::new(3).timeline(120.0, 1.0 / 120.0, &state(Mood::Dread));
+        let notes: Vec<_> = events
+            .iter()
+            .filter(|event| event.bus == Bus::Music)
+            .map(|event| event.sound.sound)
+            .collect();
+        assert!(!notes.is_empty());
+        assert!(notes
+            .iter()
+            .all(|sound| matches!(sound, SoundId::Note4 | SoundId::Note5 | SoundId::Note6)));
+    }
+}
@@ -0,0 +1,242 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use jandering_engine::{
+    object::D2Instance,
+    types::{Vec2, DEG_TO_RAD},
+};
+use rand::Rng;
+use serde::Deserialize;
+
+use super::{
+    rng::GameRng,
+    sprite_manifest::SpriteId,
+    sprite_renderer::{SpriteEffects, SpriteRenderer},
+};
+
+// a single emitter never keeps more than this alive, extra spawns are skipped
+const MAX_PARTICLES: usize = 512;
+
+// ranges are [min, max] and picked from uniformly per particle. angles are in degrees with
+// 90 straight up, speeds in pixels per second and gravity pulls downwards. area is the width
+// and height of the box around the emitter particles spawn in
+#[derive(Debug, Deserialize)]
+pub struct EmitterConfig {
+    pub sprites: Vec<SpriteId>,
+    pub burst: u32,
+    #[serde(default)]
+    pub area: [f32; 2],
+    pub lifetime: [f32; 2],
+    pub speed: [f32; 2],
+    #[serde(default)]
+    pub direction: f32,
+    #[serde(default)]
+    pub spread: f32,
+    #[serde(default)]
+    pub gravity: f32,
+    #[serde(default)]
+    pub rotation: [f32; 2],
+    #[serde(default)]
+    pub spin: [f32; 2],
+    #[serde(default = "unit_scale")]
+    pub scale: [f32; 2],
+    #[serde(default)]
+    pub fade: bool,
+    pub z_index: u32,
+}
+
+fn unit_scale() -> [f32; 2] {
+    [1.0, 1.0]
+}
+
+fn configs() -> &'static HashMap<String, EmitterConfig> {
+    static CONFIGS: OnceLock<HashMap<String, EmitterConfig>> = OnceLock::new();
+    CONFIGS.get_or_init(|| {
+        let configs: HashMap<String, EmitterConfig> =
+            serde_json::from_str(include_str!("../../res/particles.json"))
+                .unwrap_or_else(|e| panic!("failed parsing particles.json: {}", e));
+        for (name, config) in configs.iter() {
+            if config.sprites.is_empty() || config.burst == 0 || config.lifetime[0] <= 0.0 {
+                panic!(
+                    "particles {} need at least one sprite, a burst and a positive lifetime",
+                    name
+                );
+            }
+        }
+        configs
+    })
+}
+
+pub fn emitter_config(name: &str) -> &'static EmitterConfig {
+    configs()
+        .get(name)
+        .unwrap_or_else(|| panic!("no particles named {}", name))
+}
+
+fn pick(range: [f32; 2], rng: &mut GameRng) -> f32 {
+    range[0] + (range[1] - range[0]) * rng.gen::<f32>()
+}
+
+#[derive(Clone, Debug)]
+struct Particle {
+    sprite: SpriteId,
+    pos: Vec2,
+    prev_pos: Vec2,
+    velocity: Vec2,
+    rotation: f32,
+    spin: f32,
+    scale: f32,
+    age: f32,
+    lifetime: f32,
+}
+
+// particles only come out in bursts. they stay where they were spawned when the emitter
+// moves, only new ones follow it
+#[derive(Clone, Debug)]
+pub struct ParticleEmitter {
+    config: &'static EmitterConfig,
+    pub position: Vec2,
+    particles: Vec<Particle>,
+}
+
+impl ParticleEmitter {
+    pub fn new(name: &str, position: Vec2) -> Self {
+        Self {
+            config: emitter_config(name),
+            position,
+            particles: Vec::new(),
+        }
+    }
+
+    pub fn burst(&mut self, rng: &mut GameRng) {
+        (0..self.config.burst).for_each(|_| self.spawn(rng));
+    }
+
+    pub fn burst_at(&mut self, position: Vec2, rng: &mut GameRng) {
+        self.position = position;
+        self.burst(rng);
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    fn spawn(&mut self, rng: &mut GameRng) {
+        if self.particles.len() >= MAX_PARTICLES {
+            return;
+        }
+        let config = self.config;
+        let sprite = config.sprites[rng.gen_range(0..config.sprites.len())];
+        let angle = (config.direction + (rng.gen::<f32>() - 0.5) * config.spread) * DEG_TO_RAD;
+        let pos = self.position
+            + Vec2::new(
+                (rng.gen::<f32>() - 0.5) * config.area[0],
+                (rng.gen::<f32>() - 0.5) * config.area[1],
+            );
+        self.particles.push(Particle {
+            sprite,
+            pos,
+            prev_pos: pos,
+            velocity: Vec2::from_angle(angle) * pick(config.speed, rng),
+            rotation: pick(config.rotation, rng) * DEG_TO_RAD,
+            spin: pick(config.spin, rng) * DEG_TO_RAD,
+            scale: pick(config.scale, rng),
+            age: 0.0,
+            lifetime: pick(config.lifetime, rng),
+        });
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let gravity = self.config.gravity;
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+            particle.velocity.y -= gravity * dt;
+            particle.prev_pos = particle.pos;
+            particle.pos += particle.velocity * dt;
+            particle.rotation += particle.spin * dt;
+            particle.age < particle.lifetime
+        });
+    }
+
+    pub fn render(&self, sprite_renderer: &mut SpriteRenderer, alpha: f32) {
+        for particle in self.particles.iter() {
+            let effects = if self.config.fade {
+                SpriteEffects::faded(1.0 - particle.age / particle.lifetime)
+            } else {
+                SpriteEffects::default()
+            };
+            sprite_renderer.render_with_effects(
+                D2Instance {
+                    position: particle.prev_pos.lerp(particle.pos, alpha),
+                    rotation: particle.rotation,
+                    ..Default::default()
+                },
+                particle.sprite,
+                self.config.z_index,
+                particle.scale,
+                effects,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{constants::TICK_LENGTH, rng};
+
+    fn step(emitter: &mut ParticleEmitter, seconds: f32) {
+        for _ in 0..(seconds / TICK_LENGTH).round() as usize {
+            emitter.update(TICK_LENGTH);
+        }
+    }
+
+    #[test]
+    fn bursts_spawn_the_configured_count_in_the_area() {
+        let mut rng = rng::seeded(1);
+        let mut leaves = ParticleEmitter::new("falling_leaves", Vec2::ZERO);
+        leaves.burst_at(Vec2::new(0.0, 300.0), &mut rng);
+        assert_eq!(leaves.particles.len(), 8);
+        assert!(leaves
+            .particles
+            .iter()
+            .all(|particle| particle.pos.x.abs() <= 600.0 && particle.pos.y == 300.0));
+
+        leaves.burst(&mut rng);
+        assert_eq!(leaves.particles.len(), 16);
+    }
+
+    #[test]
+    fn bursts_stop_at_the_particle_limit() {
+        let mut rng = rng::seeded(2);
+        let mut droplets = ParticleEmitter::new("water_droplets", Vec2::ZERO);
+        for _ in 0..100 {
+            droplets.burst(&mut rng);
+        }
+        assert_eq!(droplets.particles.len(), MAX_PARTICLES);
+    }
+
+    #[test]
+    fn particles_expire_after_their_lifetime() {
+        let mut rng = rng::seeded(3);
+        // lifetimes are picked from 0.35 to 0.6
+        let mut droplets = ParticleEmitter::new("water_droplets", Vec2::ZERO);
+        droplets.burst(&mut rng);
+        step(&mut droplets, 0.3);
+        assert_eq!(droplets.particles.len(), 10);
+        step(&mut droplets, 0.35);
+        assert!(droplets.particles.is_empty());
+    }
+
+    #[test]
+    fn clear_drops_every_particle() {
+        let mut rng = rng::seeded(4);
+        let mut sparkles = ParticleEmitter::new("coin_sparkle", Vec2::ZERO);
+        sparkles.burst(&mut rng);
+        sparkles.clear();
+        assert!(sparkles.particles.is_empty());
+
+        // later bursts still work
+        sparkles.burst(&mut rng);
+        assert_eq!(sparkles.particles.len(), 6);
+    }
+}
@@ -1,18 +1,24 @@
 use crate::{clickable_nohover, game::clickableobject::ObjectSprite};
 use jandering_engine::{object::D2Instance, types::Vec2};
+use serde::{Deserialize, Serialize};
 
 use crate::clickable;
 
-use super::{clickableobject::ClickableObject, sprite_renderer::SpriteRenderer};
+use super::{
+    clickableobject::ClickableObject,
+    save::PlantSave,
+    sprite_manifest::{hovered, sprite_size, SpriteId},
+    sprite_renderer::{SpriteEffects, SpriteRenderer},
+};
 
-#[derive(Eq, PartialEq, std::hash::Hash, Clone, Copy)]
+#[derive(Eq, PartialEq, std::hash::Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum PlantType {
     Strawberry,
     Flower,
     Watermelon,
 }
 
-#[derive(Eq, PartialEq, std::hash::Hash, Clone, Copy)]
+#[derive(Eq, PartialEq, std::hash::Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum PlantState {
     Growing,
     Harvestable,
@@ -30,14 +36,14 @@ pub struct Plant {
 }
 
 impl Plant {
-    pub fn new(plant_type: PlantType, sprite_renderer: &mut SpriteRenderer) -> Self {
+    pub fn new(plant_type: PlantType) -> Self {
         let object = match plant_type {
             PlantType::Strawberry => {
-                clickable_nohover!(0.0, 0.0, "plants_strawberry", sprite_renderer)
+                clickable_nohover!(0.0, 0.0, SpriteId::PlantsStrawberry)
             }
-            PlantType::Flower => clickable_nohover!(0.0, 0.0, "plants_flower", sprite_renderer),
+            PlantType::Flower => clickable_nohover!(0.0, 0.0, SpriteId::PlantsFlower),
             PlantType::Watermelon => {
-                clickable_nohover!(0.0, 0.0, "plants_watermelon1", sprite_renderer)
+                clickable_nohover!(0.0, 0.0, SpriteId::PlantsWatermelon1)
             }
         };
         Self {
@@ -50,11 +56,26 @@ impl Plant {
         }
     }
 
-    pub fn update(&mut self, sprite_renderer: &mut SpriteRenderer) {
+    pub fn restore(save: &PlantSave) -> Self {
+        let mut plant = Self::new(save.plant_type);
+        plant.set_growth(save.growth);
+        plant.state = save.state;
+        plant.watered = save.watered;
+        plant
+    }
+
+    pub fn save(&self) -> PlantSave {
+        PlantSave {
+            plant_type: self.plant_type,
+            growth: self.growth,
+            state: self.state,
+            watered: self.watered,
+        }
+    }
+
+    pub fn update(&mut self) {
         if self.update_sprite {
-            self.object.size = sprite_renderer
-                .get_sprite(self.object.get_current_frame())
-                .size;
+            self.object.size = sprite_size(self.object.get_current_frame());
             self.update_sprite = false;
         }
     }
@@ -84,42 +105,42 @@ impl Plant {
         let mut hovered = None;
         let tex = match self.plant_type {
             PlantType::Strawberry => match self.growth {
-                0 => "plants_strawberry",
-                1..=3 => "plants_strawberry1",
+                0 => SpriteId::PlantsStrawberry,
+                1..=3 => SpriteId::PlantsStrawberry1,
                 4 => {
                     self.state = PlantState::Harvestable;
-                    hovered = Some("plants_strawberry2_hovered");
-                    "plants_strawberry2"
+                    hovered = Some(hovered::PlantsStrawberry2);
+                    SpriteId::PlantsStrawberry2
                 }
                 _ => {
                     self.state = PlantState::Dead;
-                    "plants_strawberry3"
+                    SpriteId::PlantsStrawberry3
                 }
             },
             PlantType::Flower => match self.growth {
-                0 => "plants_flower",
+                0 => SpriteId::PlantsFlower,
                 1 => {
                     self.state = PlantState::Harvestable;
-                    hovered = Some("plants_flower1_hovered");
-                    "plants_flower1"
+                    hovered = Some(hovered::PlantsFlower1);
+                    SpriteId::PlantsFlower1
                 }
                 _ => {
                     self.state = PlantState::Dead;
-                    "plants_flower2"
+                    SpriteId::PlantsFlower2
                 }
             },
             PlantType::Watermelon => match self.growth {
-                0 => "plants_watermelon1",
-                1..=2 => "plants_watermelon2",
-                3..=4 => "plants_watermelon3",
+                0 => SpriteId::PlantsWatermelon1,
+                1..=2 => SpriteId::PlantsWatermelon2,
+                3..=4 => SpriteId::PlantsWatermelon3,
                 5 => {
                     self.state = PlantState::Harvestable;
-                    hovered = Some("plants_watermelon4_hovered");
-                    "plants_watermelon4"
+                    hovered = Some(hovered::PlantsWatermelon4);
+                    SpriteId::PlantsWatermelon4
                 }
                 _ => {
                     self.state = PlantState::Dead;
-                    "plants_watermelon5"
+                    SpriteId::PlantsWatermelon5
                 }
             },
         };
@@ -160,16 +181,22 @@ impl Plant {
     }
 
     pub fn render(&mut self, sprite_renderer: &mut SpriteRenderer) {
+        // dead plants wilt towards a dry brown
+        self.object.effects.color = if matches!(self.state, PlantState::Dead) {
+            [0.75, 0.65, 0.5, 1.0]
+        } else {
+            [1.0, 1.0, 1.0, 1.0]
+        };
         self.object.render(sprite_renderer);
         if let Some(sprite) = match self.state {
             PlantState::Growing => {
                 if !self.watered {
-                    Some("garden_water")
+                    Some(SpriteId::GardenWater)
                 } else {
                     None
                 }
             }
-            PlantState::Harvestable => Some("plants_coins"),
+            PlantState::Harvestable => Some(SpriteId::PlantsCoins),
             PlantState::Dead => None,
         } {
             let position = self.object.position + Vec2::new(-30.0, 60.0);
@@ -185,13 +212,10 @@ impl Plant {
     }
 }
 
-pub fn seed_packet_from_plant(
-    plant_type: PlantType,
-    sprite_renderer: &mut SpriteRenderer,
-) -> ClickableObject {
+pub fn seed_packet_from_plant(plant_type: PlantType) -> ClickableObject {
     match plant_type {
-        PlantType::Strawberry => clickable!(0.0, 0.0, "market_seeds_strawberry", sprite_renderer),
-        PlantType::Flower => clickable!(0.0, 0.0, "market_seeds_flower", sprite_renderer),
-        PlantType::Watermelon => clickable!(0.0, 0.0, "market_seeds_watermelon", sprite_renderer),
+        PlantType::Strawberry => clickable!(0.0, 0.0, MarketSeedsStrawberry),
+        PlantType::Flower => clickable!(0.0, 0.0, MarketSeedsFlower),
+        PlantType::Watermelon => clickable!(0.0, 0.0, MarketSeedsWatermelon),
     }
 }
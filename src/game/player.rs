@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use super::plant::PlantType;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Player {
     pub hp: f32,
     pub coins: u32,
@@ -16,4 +19,6 @@ pub struct Player {
     pub used_eye: bool,
 
     pub has_page: bool,
+
+    pub days: u32,
 }
@@ -18,10 +18,29 @@ pub struct PostProcessing {
     factor_bg: BindGroupHandle<PoprBindGroup>,
     bind_groups: [UntypedBindGroupHandle; 3],
     pub target_texture: TextureHandle,
+}
+
+// everything gameplay touches lives here so it can run without a renderer
+pub struct PostProcessingState {
     pub darkness: f32,
     pub time: f32,
     pub distortion: f32,
     pub vignette: f32,
+    pub max_distortion: Option<f32>,
+    pub reduced_motion: bool,
+}
+
+impl Default for PostProcessingState {
+    fn default() -> Self {
+        Self {
+            darkness: 0.0,
+            time: 0.0,
+            distortion: 0.7,
+            vignette: 1.0,
+            max_distortion: None,
+            reduced_motion: false,
+        }
+    }
 }
 
 impl PostProcessing {
@@ -77,20 +96,27 @@ impl PostProcessing {
             bind_groups,
             target_texture,
             factor_bg,
-            darkness: 0.0,
-            time: 0.0,
-            distortion: 0.7,
-            vignette: 1.0,
         }
     }
 
-    pub fn render_tonemap(&mut self, renderer: &mut Renderer, context: &mut EngineContext) {
-        self.time += self.distortion * context.dt as f32;
+    pub fn render_tonemap(
+        &mut self,
+        state: &mut PostProcessingState,
+        renderer: &mut Renderer,
+        context: &mut EngineContext,
+    ) {
+        let distortion = match state.max_distortion {
+            Some(max) => state.distortion.min(max),
+            None => state.distortion,
+        };
+        if !state.reduced_motion {
+            state.time += distortion * context.dt as f32;
+        }
         let factor = renderer.get_bind_group_t_mut(self.factor_bg).unwrap();
-        factor.uniform.factor = 1.0 - self.darkness;
-        factor.uniform.time = self.time;
-        factor.uniform.distortion = self.distortion;
-        factor.uniform.vignette = self.vignette;
+        factor.uniform.factor = 1.0 - state.darkness;
+        factor.uniform.time = state.time;
+        factor.uniform.distortion = distortion;
+        factor.uniform.vignette = state.vignette;
 
         renderer.set_target_surface();
         renderer.render(&[&self.quad], context, &self.fade_shader, &self.bind_groups);
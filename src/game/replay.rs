@@ -0,0 +1,274 @@
+use jandering_engine::types::Vec2;
+use serde::{Deserialize, Serialize};
+
+use super::{constants::SAVE_SLOTS, save, storage::Storage, InputInfo};
+
+// 2: mouse positions are screen space, from before the camera is applied
+// 3: frames are stored as runs
+pub const REPLAY_VERSION: u32 = 3;
+
+const REPLAY_KEY: &str = "gardenere_replay";
+
+const LEFT_PRESSED: u8 = 1;
+const LEFT_RELEASED: u8 = 2;
+const RIGHT_PRESSED: u8 = 4;
+const RIGHT_RELEASED: u8 = 8;
+
+// dt, button bits and mouse position for one tick
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReplayFrame(f32, u8, Option<[f32; 2]>);
+
+impl ReplayFrame {
+    pub fn record(dt: f32, input: &InputInfo) -> Self {
+        let buttons = [
+            (input.left_pressed, LEFT_PRESSED),
+            (input.left_released, LEFT_RELEASED),
+            (input.right_pressed, RIGHT_PRESSED),
+            (input.right_released, RIGHT_RELEASED),
+        ]
+        .iter()
+        .filter(|(down, _)| *down)
+        .fold(0, |bits, (_, bit)| bits | bit);
+
+        Self(dt, buttons, input.mouse_screen.map(|pos| pos.to_array()))
+    }
+
+    pub fn apply(&self, input: &mut InputInfo) -> f32 {
+        let Self(dt, buttons, mouse_pos) = *self;
+        input.left_pressed = buttons & LEFT_PRESSED != 0;
+        input.left_released = buttons & LEFT_RELEASED != 0;
+        input.right_pressed = buttons & RIGHT_PRESSED != 0;
+        input.right_released = buttons & RIGHT_RELEASED != 0;
+        input.mouse_screen = mouse_pos.map(Vec2::from_array);
+        dt
+    }
+}
+
+// a value and how many frames in a row it held. dt hardly ever changes, buttons are 0
+// outside the odd click and the mouse sits still a lot, so each track ends up short
+#[derive(Serialize, Deserialize)]
+struct Runs<T>(Vec<(u32, T)>);
+
+impl<T> Default for Runs<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: Copy + PartialEq> Runs<T> {
+    fn push(&mut self, value: T) {
+        match self.0.last_mut() {
+            Some((count, last)) if *last == value => *count += 1,
+            _ => self.0.push((1, value)),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.0
+            .iter()
+            .flat_map(|&(count, value)| std::iter::repeat(value).take(count as usize))
+    }
+}
+
+// each part of a frame is its own track, so a change in one doesn't break the others' runs
+#[derive(Default, Serialize, Deserialize)]
+struct ReplayFrames {
+    dt: Runs<f32>,
+    buttons: Runs<u8>,
+    mouse: Runs<Option<[f32; 2]>>,
+}
+
+// the save slots are captured when recording starts, so loading a slot plays back the same way
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub slots: Vec<Option<String>>,
+    frames: ReplayFrames,
+    frame_count: usize,
+}
+
+impl Replay {
+    pub fn new(seed: u64, storage: &dyn Storage) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            slots: (0..SAVE_SLOTS)
+                .map(|slot| storage.read(&save::slot_key(slot)))
+                .collect(),
+            frames: ReplayFrames::default(),
+            frame_count: 0,
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    pub fn push(&mut self, dt: f32, input: &InputInfo) {
+        let ReplayFrame(dt, buttons, mouse) = ReplayFrame::record(dt, input);
+        self.frames.dt.push(dt);
+        self.frames.buttons.push(buttons);
+        self.frames.mouse.push(mouse);
+        self.frame_count += 1;
+    }
+
+    pub fn frames(&self) -> Vec<ReplayFrame> {
+        self.frames
+            .dt
+            .iter()
+            .zip(self.frames.buttons.iter())
+            .zip(self.frames.mouse.iter())
+            .map(|((dt, buttons), mouse)| ReplayFrame(dt, buttons, mouse))
+            .collect()
+    }
+
+    pub fn restore_slots(&self, storage: &mut dyn Storage) {
+        for (slot, text) in self.slots.iter().enumerate() {
+            if let Some(text) = text {
+                if let Err(e) = storage.write(&save::slot_key(slot), text) {
+                    log::error!("failed restoring slot {} for replay: {:?}", slot, e);
+                }
+            }
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("replays are always serializable")
+    }
+
+    pub fn store(&self, storage: &mut dyn Storage) {
+        if let Err(e) = storage.write(REPLAY_KEY, &self.to_json()) {
+            log::error!("failed writing replay: {:?}", e);
+        }
+    }
+
+    pub fn fetch(storage: &dyn Storage) -> Option<Self> {
+        let text = storage.read(REPLAY_KEY)?;
+        match serde_json::from_str::<Self>(&text) {
+            Ok(replay) if replay.version == REPLAY_VERSION => Some(replay),
+            Ok(replay) => {
+                log::error!("replay version {} isn't supported", replay.version);
+                None
+            }
+            Err(e) => {
+                log::error!("failed loading replay: {}", e);
+                None
+            }
+        }
+    }
+
+    fn file_name(&self) -> String {
+        format!("gardenere_replay_{}.json", self.seed)
+    }
+
+    // hands the replay to the browser as a download
+    #[cfg(target_arch = "wasm32")]
+    pub fn export(&self) {
+        if let Err(e) = download(&self.file_name(), &self.to_json()) {
+            log::error!("failed exporting replay: {:?}", e);
+        }
+    }
+
+    // written to the working directory, next to wherever the game was started from
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export(&self) {
+        let path = self.file_name();
+        match std::fs::write(&path, self.to_json()) {
+            Ok(()) => log::info!("replay exported to {}", path),
+            Err(e) => log::error!("failed exporting replay: {}", e),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn download(file_name: &str, text: &str) -> Result<(), wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsCast;
+
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(text));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let link: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+pub struct Playback {
+    frames: Vec<ReplayFrame>,
+    frame: usize,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            frames: replay.frames(),
+            frame: 0,
+        }
+    }
+
+    // feeds the next recorded frame into input and returns its dt, None once it ran out
+    pub fn next(&mut self, input: &mut InputInfo) -> Option<f32> {
+        let frame = self.frames.get(self.frame)?;
+        self.frame += 1;
+        Some(frame.apply(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::storage::MemoryStorage;
+
+    #[test]
+    fn frames_round_trip_through_storage() {
+        let mut storage = MemoryStorage::default();
+        let mut replay = Replay::new(7, &storage);
+        let mut input = InputInfo::default();
+        let mut recorded = Vec::new();
+        for tick in 0..600 {
+            input.mouse_screen = Some(Vec2::new((tick / 50) as f32 * 3.5, -20.0));
+            input.left_pressed = tick % 200 == 10;
+            input.left_released = tick % 200 == 40;
+            let dt = if tick == 300 { 0.02 } else { 1.0 / 120.0 };
+            replay.push(dt, &input);
+            recorded.push(ReplayFrame::record(dt, &input));
+        }
+        replay.store(&mut storage);
+
+        let fetched = Replay::fetch(&storage).expect("stored replays can be fetched");
+        assert_eq!(fetched.seed, 7);
+        assert_eq!(fetched.frame_count(), recorded.len());
+        assert_eq!(fetched.frames(), recorded);
+        // 600 frames but only a handful of runs
+        assert_eq!(fetched.frames.dt.0.len(), 3);
+        assert_eq!(fetched.frames.buttons.0.len(), 13);
+        assert_eq!(fetched.frames.mouse.0.len(), 12);
+    }
+
+    #[test]
+    fn playback_feeds_frames_back_in_order() {
+        let mut replay = Replay::new(0, &MemoryStorage::default());
+        let mut input = InputInfo::default();
+        input.right_pressed = true;
+        replay.push(0.5, &input);
+        input = InputInfo::default();
+        replay.push(0.25, &input);
+
+        let mut playback = Playback::new(replay);
+        let mut played = InputInfo::default();
+        assert_eq!(playback.next(&mut played), Some(0.5));
+        assert!(played.right_pressed);
+        assert_eq!(playback.next(&mut played), Some(0.25));
+        assert!(!played.right_pressed);
+        assert_eq!(playback.next(&mut played), None);
+    }
+}
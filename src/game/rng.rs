@@ -0,0 +1,30 @@
+use rand::SeedableRng;
+
+use super::launch;
+
+// pcg is small, fast and serializable, so the exact rng state can go into saves
+pub type GameRng = rand_pcg::Pcg32;
+
+pub fn seeded(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
+
+// particles and sound variations draw from this instead of the run rng, so how a run
+// looks and sounds never changes how it plays
+pub fn effects(seed: u64) -> GameRng {
+    seeded(seed ^ 0x9e37_79b9_7f4a_7c15)
+}
+
+// the music's own stream, a copy of the run rng would pick notes in lockstep with it
+pub fn music(seed: u64) -> GameRng {
+    seeded(seed ^ 0xd1b5_4a32_d192_ed03)
+}
+
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+// only new games are started with it, loaded runs carry on with the rng they were saved with
+pub fn requested_seed() -> Option<u64> {
+    launch::option("seed")?.parse().ok()
+}
@@ -0,0 +1,382 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    main_plant::{MainPlant, MainPlantStage},
+    plant::{PlantState, PlantType},
+    player::Player,
+    post_processing::PostProcessingState,
+    rng::{self, GameRng},
+    scenes::{ActiveScene, Scenes},
+    storage::Storage,
+};
+
+pub const SAVE_VERSION: u32 = 3;
+
+const SAVE_KEY: &str = "gardenere_save";
+
+// MIGRATIONS[i] upgrades a save from version i + 1 to version i + 2
+const MIGRATIONS: &[fn(&mut Value)] = &[add_days, add_rng];
+
+#[derive(Debug)]
+pub enum SaveError {
+    Parse(serde_json::Error),
+    MissingVersion,
+    TooNew(u32),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PlantSave {
+    pub plant_type: PlantType,
+    pub growth: u32,
+    pub state: PlantState,
+    pub watered: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PotSave {
+    pub position: [f32; 2],
+    pub plant: Option<PlantSave>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MainPlantSave {
+    pub growth: u32,
+    pub stage: MainPlantStage,
+    pub requires_blood: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PostProcessingSave {
+    pub distortion: f32,
+    pub vignette: f32,
+}
+
+pub struct SlotSummary {
+    pub days: u32,
+    pub coins: u32,
+    pub stage: MainPlantStage,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub player: Player,
+    pub main_plant: MainPlantSave,
+    pub pots: Vec<PotSave>,
+    pub active_scene: ActiveScene,
+    pub post_processing: PostProcessingSave,
+    pub seed: u64,
+    pub rng: GameRng,
+}
+
+impl SaveData {
+    pub fn capture(
+        player: &Player,
+        main_plant: &MainPlant,
+        scenes: &Scenes,
+        popr: &PostProcessingState,
+        seed: u64,
+        rng: &GameRng,
+    ) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            player: player.clone(),
+            main_plant: MainPlantSave {
+                growth: main_plant.growth,
+                stage: main_plant.stage,
+                requires_blood: main_plant.requires_blood,
+            },
+            pots: scenes.garden.save_pots(),
+            active_scene: scenes.active_scene,
+            post_processing: PostProcessingSave {
+                distortion: popr.distortion,
+                vignette: popr.vignette,
+            },
+            seed,
+            rng: rng.clone(),
+        }
+    }
+
+    pub fn apply(
+        &self,
+        player: &mut Player,
+        main_plant: &mut MainPlant,
+        scenes: &mut Scenes,
+        popr: &mut PostProcessingState,
+        rng: &mut GameRng,
+    ) {
+        *player = self.player.clone();
+
+        main_plant.growth = self.main_plant.growth;
+        main_plant.requires_blood = self.main_plant.requires_blood;
+        main_plant.set_stage(self.main_plant.stage);
+
+        scenes.garden.load_pots(&self.pots);
+        scenes.set_scene(self.active_scene);
+
+        popr.distortion = self.post_processing.distortion;
+        popr.vignette = self.post_processing.vignette;
+
+        *rng = self.rng.clone();
+    }
+
+    pub fn summary(&self) -> SlotSummary {
+        SlotSummary {
+            days: self.player.days,
+            coins: self.player.coins,
+            stage: self.main_plant.stage,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("save data is always serializable")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SaveError> {
+        let mut value: Value = serde_json::from_str(text).map_err(SaveError::Parse)?;
+        migrate(&mut value)?;
+        serde_json::from_value(value).map_err(SaveError::Parse)
+    }
+}
+
+pub fn migrate(value: &mut Value) -> Result<(), SaveError> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(SaveError::MissingVersion)? as u32;
+
+    if version > SAVE_VERSION {
+        return Err(SaveError::TooNew(version));
+    }
+
+    for migration in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+        migration(value);
+    }
+    value["version"] = Value::from(SAVE_VERSION);
+
+    Ok(())
+}
+
+fn add_days(value: &mut Value) {
+    value["player"]["days"] = Value::from(0);
+}
+
+// runs saved before seeding get a fresh seed, they weren't reproducible anyway
+fn add_rng(value: &mut Value) {
+    let seed = rng::random_seed();
+    value["seed"] = Value::from(seed);
+    value["rng"] = serde_json::to_value(rng::seeded(seed)).expect("rng is always serializable");
+}
+
+pub fn slot_key(slot: usize) -> String {
+    format!("{}_{}", SAVE_KEY, slot)
+}
+
+// before slots the one save lived under the bare key, it becomes slot 0 unless that's taken
+pub fn adopt_legacy_save(storage: &mut dyn Storage) {
+    let Some(text) = storage.read(SAVE_KEY) else {
+        return;
+    };
+    if storage.read(&slot_key(0)).is_some() {
+        return;
+    }
+    match storage.write(&slot_key(0), &text) {
+        Ok(()) => storage.remove(SAVE_KEY),
+        Err(e) => log::error!("failed moving the old save into slot 0: {:?}", e),
+    }
+}
+
+pub fn store(storage: &mut dyn Storage, slot: usize, save: &SaveData) {
+    if let Err(e) = storage.write(&slot_key(slot), &save.to_json()) {
+        log::error!("failed writing save to slot {}: {:?}", slot, e);
+    }
+}
+
+pub fn fetch(storage: &dyn Storage, slot: usize) -> Option<SaveData> {
+    let text = storage.read(&slot_key(slot))?;
+    match SaveData::from_json(&text) {
+        Ok(save) => Some(save),
+        Err(e) => {
+            log::error!("failed loading save from slot {}: {:?}", slot, e);
+            None
+        }
+    }
+}
+
+pub fn clear(storage: &mut dyn Storage, slot: usize) {
+    storage.remove(&slot_key(slot));
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::game::{simulation::make_everything, storage::MemoryStorage};
+
+    // a version 1 save, from before days and the run rng were stored
+    const V1_SAVE: &str = r#"{
+        "version": 1,
+        "player": {
+            "hp": 80.0, "coins": 7, "total_coins": 12, "owned_seeds": { "Flower": 2 },
+            "owned_pots": 3, "has_axe": true, "cut_finger": true, "used_finger": false,
+            "cut_eye": false, "used_eye": false, "has_page": false
+        },
+        "main_plant": { "growth": 4, "stage": "Second", "requires_blood": true },
+        "pots": [{
+            "position": [10.0, -20.0],
+            "plant": { "plant_type": "Strawberry", "growth": 2, "state": "Growing", "watered": true }
+        }],
+        "active_scene": "Garden",
+        "post_processing": { "distortion": 1.5, "vignette": 0.8 }
+    }"#;
+
+    #[test]
+    fn capture_round_trips_through_storage() {
+        let (mut scenes, mut player, mut main_plant) = make_everything();
+        player.coins = 17;
+        player.days = 4;
+        player.cut_finger = true;
+        player.owned_seeds.insert(PlantType::Flower, 2);
+        main_plant.growth = 5;
+        main_plant.requires_blood = true;
+        main_plant.set_stage(MainPlantStage::Third);
+        scenes.garden.load_pots(&[
+            PotSave {
+                position: [12.0, -40.0],
+                plant: Some(PlantSave {
+                    plant_type: PlantType::Strawberry,
+                    growth: 1,
+                    state: PlantState::Growing,
+                    watered: true,
+                }),
+            },
+            PotSave {
+                position: [-80.0, 10.0],
+                plant: None,
+            },
+        ]);
+        scenes.set_scene(ActiveScene::Market);
+        let popr = PostProcessingState {
+            distortion: 2.5,
+            vignette: 0.6,
+            ..Default::default()
+        };
+        let mut run_rng = rng::seeded(42);
+        run_rng.gen::<u64>();
+
+        let save = SaveData::capture(&player, &main_plant, &scenes, &popr, 42, &run_rng);
+        let mut storage = MemoryStorage::default();
+        store(&mut storage, 1, &save);
+        let loaded = fetch(&storage, 1).expect("stored save loads back");
+
+        let (mut new_scenes, mut new_player, mut new_main_plant) = make_everything();
+        let mut new_popr = PostProcessingState::default();
+        let mut new_rng = rng::seeded(0);
+        loaded.apply(
+            &mut new_player,
+            &mut new_main_plant,
+            &mut new_scenes,
+            &mut new_popr,
+            &mut new_rng,
+        );
+
+        assert_eq!(new_player.coins, 17);
+        assert_eq!(new_player.days, 4);
+        assert!(new_player.cut_finger);
+        assert_eq!(new_player.owned_seeds.get(&PlantType::Flower), Some(&2));
+        assert_eq!(new_main_plant.growth, 5);
+        assert!(new_main_plant.requires_blood);
+        assert!(matches!(new_main_plant.stage, MainPlantStage::Third));
+        assert!(matches!(new_scenes.active_scene, ActiveScene::Market));
+        assert_eq!(
+            serde_json::to_value(new_scenes.garden.save_pots()).unwrap(),
+            serde_json::to_value(&save.pots).unwrap()
+        );
+        assert_eq!(new_popr.distortion, 2.5);
+        assert_eq!(new_popr.vignette, 0.6);
+        assert_eq!(new_rng, run_rng);
+        assert_eq!(loaded.seed, 42);
+
+        let recaptured = SaveData::capture(
+            &new_player,
+            &new_main_plant,
+            &new_scenes,
+            &new_popr,
+            loaded.seed,
+            &new_rng,
+        );
+        assert_eq!(recaptured.to_json(), save.to_json());
+    }
+
+    #[test]
+    fn add_days_starts_old_runs_at_day_zero() {
+        let mut value: Value = serde_json::from_str(V1_SAVE).unwrap();
+        add_days(&mut value);
+        assert_eq!(value["player"]["days"], Value::from(0));
+        assert_eq!(value["player"]["coins"], Value::from(7));
+    }
+
+    #[test]
+    fn add_rng_gives_a_usable_seeded_rng() {
+        let mut value: Value = serde_json::from_str(V1_SAVE).unwrap();
+        add_rng(&mut value);
+        let seed = value["seed"].as_u64().expect("seed is added");
+        let saved_rng: GameRng = serde_json::from_value(value["rng"].clone()).unwrap();
+        assert_eq!(saved_rng, rng::seeded(seed));
+    }
+
+    #[test]
+    fn version_1_saves_migrate_to_the_current_version() {
+        let save = SaveData::from_json(V1_SAVE).expect("v1 saves still load");
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.player.days, 0);
+        assert_eq!(save.player.coins, 7);
+        assert_eq!(save.main_plant.growth, 4);
+        assert_eq!(save.pots.len(), 1);
+        assert_eq!(save.rng, rng::seeded(save.seed));
+    }
+
+    #[test]
+    fn version_2_saves_keep_their_days() {
+        let mut value: Value = serde_json::from_str(V1_SAVE).unwrap();
+        value["version"] = Value::from(2);
+        value["player"]["days"] = Value::from(9);
+        let save = SaveData::from_json(&value.to_string()).expect("v2 saves still load");
+        assert_eq!(save.player.days, 9);
+        assert_eq!(save.rng, rng::seeded(save.seed));
+    }
+
+    #[test]
+    fn newer_saves_are_rejected() {
+        let mut value: Value = serde_json::from_str(V1_SAVE).unwrap();
+        value["version"] = Value::from(SAVE_VERSION + 1);
+        assert!(matches!(
+            SaveData::from_json(&value.to_string()),
+            Err(SaveError::TooNew(_))
+        ));
+    }
+
+    #[test]
+    fn legacy_saves_move_into_an_empty_slot_0() {
+        let mut storage = MemoryStorage::default();
+        storage.write(SAVE_KEY, V1_SAVE).unwrap();
+        adopt_legacy_save(&mut storage);
+
+        assert!(storage.read(SAVE_KEY).is_none());
+        let save = fetch(&storage, 0).expect("the old save loads from slot 0");
+        assert_eq!(save.player.coins, 7);
+    }
+
+    #[test]
+    fn legacy_saves_leave_a_taken_slot_0_alone() {
+        let mut storage = MemoryStorage::default();
+        storage.write(SAVE_KEY, V1_SAVE).unwrap();
+        storage.write(&slot_key(0), "newer").unwrap();
+        adopt_legacy_save(&mut storage);
+
+        assert_eq!(storage.read(&slot_key(0)).as_deref(), Some("newer"));
+        assert!(storage.read(SAVE_KEY).is_some());
+    }
+}
@@ -1,6 +1,7 @@
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::game::{clickableobject::ObjectAction, sprite_renderer::SpriteRenderer};
-use jandering_engine::{engine::EngineContext, object::D2Instance};
+use jandering_engine::object::D2Instance;
 
 use super::Scene;
 
@@ -10,7 +11,7 @@ pub struct CuttingScene {
 }
 
 impl CuttingScene {
-    pub fn new(_sprite_renderer: &mut SpriteRenderer) -> Self {
+    pub fn new() -> Self {
         Self {
             stage: 0,
             fade_time: -1.0,
@@ -19,19 +20,15 @@ impl CuttingScene {
 }
 
 impl Scene for CuttingScene {
-    fn refresh(&mut self, _data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {}
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        let dt = context.dt as f32;
+    fn refresh(&mut self, _data: &mut GameData) {}
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
         if self.fade_time >= 0.0 {
             data.popr.distortion += dt * 2.0;
             self.fade_time += dt;
             if self.fade_time > 1.0 && self.fade_time - dt < 1.0 {
                 self.stage += 1;
+                data.camera.add_trauma(0.6);
+                data.camera.zoom_to(1.0 + self.stage as f32 * 0.05, 1.0);
             }
             data.popr.darkness = 1.0 - (self.fade_time - 1.0).abs();
             if self.fade_time >= 2.0 {
@@ -53,16 +50,16 @@ impl Scene for CuttingScene {
     fn render(&mut self, _data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
         match self.stage {
             0 => {
-                sprite_renderer.render(D2Instance::default(), "mainplant_cutting1", 0);
+                sprite_renderer.render(D2Instance::default(), SpriteId::MainplantCutting1, 0);
             }
             1 => {
-                sprite_renderer.render(D2Instance::default(), "mainplant_cutting2", 0);
+                sprite_renderer.render(D2Instance::default(), SpriteId::MainplantCutting2, 0);
             }
             2 => {
-                sprite_renderer.render(D2Instance::default(), "mainplant_cutting3", 0);
+                sprite_renderer.render(D2Instance::default(), SpriteId::MainplantCutting3, 0);
             }
             3 => {
-                sprite_renderer.render(D2Instance::default(), "mainplant_cutting4", 0);
+                sprite_renderer.render(D2Instance::default(), SpriteId::MainplantCutting4, 0);
             }
             _ => {}
         }
@@ -1,6 +1,7 @@
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::game::{clickableobject::ObjectAction, sprite_renderer::SpriteRenderer};
-use jandering_engine::{engine::EngineContext, object::D2Instance};
+use jandering_engine::object::D2Instance;
 use rand::Rng;
 
 use super::Scene;
@@ -10,11 +11,11 @@ pub struct DyingScene {
     next_black_timer: f32,
     black_timer: f32,
     n_fades: u32,
-    plants: Option<&'static str>,
+    plants: Option<SpriteId>,
 }
 
 impl DyingScene {
-    pub fn new(_sprite_renderer: &mut SpriteRenderer) -> Self {
+    pub fn new() -> Self {
         Self {
             stage: 0,
             next_black_timer: 2.0,
@@ -26,15 +27,8 @@ impl DyingScene {
 }
 
 impl Scene for DyingScene {
-    fn refresh(&mut self, _data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {}
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        let dt = context.dt as f32;
-
+    fn refresh(&mut self, _data: &mut GameData) {}
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
         data.popr.distortion +=
             data.popr.distortion * dt * (data.popr.distortion / 100.0).clamp(0.15, 5.0);
 
@@ -50,13 +44,16 @@ impl Scene for DyingScene {
             self.next_black_timer = 2.0;
             self.black_timer = 0.1;
             self.n_fades += 1;
+            // creeps in a little closer with every flash
+            data.camera.add_trauma(0.4);
+            data.camera.zoom_to(1.0 + self.n_fades as f32 * 0.03, 2.0);
             let random = data.rng.gen::<u32>() % 9;
             self.plants = if random < self.n_fades {
-                Some("mainplant_killed_plants2")
+                Some(SpriteId::MainplantKilledPlants2)
             } else if random > self.n_fades + 3 {
                 None
             } else {
-                Some("mainplant_killed_plants1")
+                Some(SpriteId::MainplantKilledPlants1)
             };
         }
 
@@ -68,7 +65,7 @@ impl Scene for DyingScene {
     }
 
     fn render(&mut self, _data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
-        sprite_renderer.render(D2Instance::default(), "mainplant_killed_bg", 0);
+        sprite_renderer.render(D2Instance::default(), SpriteId::MainplantKilledBg, 0);
 
         if let Some(plants) = self.plants {
             sprite_renderer.render(D2Instance::default(), plants, 2);
@@ -1,5 +1,6 @@
 use crate::game::clickableobject::ObjectSprite;
 use crate::game::main_plant::MainPlantStage;
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::{
     clickable,
@@ -9,7 +10,7 @@ use crate::{
         sprite_renderer::SpriteRenderer,
     },
 };
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 
 use super::{ActiveScene, Scene};
 
@@ -22,11 +23,11 @@ pub struct FrontScene {
 }
 
 impl FrontScene {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let market = clickable!(-336.0, 118.0, "front_market", sprite_renderer);
-        let garden = clickable!(300.0, 60.0, "front_garden", sprite_renderer);
-        let house = clickable!(-60.0, -261.0, "front_house", sprite_renderer);
-        let page = clickable!(51.0, -165.0, "front_page", sprite_renderer);
+    pub fn new() -> Self {
+        let market = clickable!(-336.0, 118.0, FrontMarket);
+        let garden = clickable!(300.0, 60.0, FrontGarden);
+        let house = clickable!(-60.0, -261.0, FrontHouse);
+        let page = clickable!(51.0, -165.0, FrontPage);
         Self {
             market,
             garden,
@@ -38,32 +39,26 @@ impl FrontScene {
 }
 
 impl Scene for FrontScene {
-    fn refresh(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
+    fn refresh(&mut self, data: &mut GameData) {
         if data.main_plant.requires_blood {
             self.mainplant_blood = true;
         }
 
         if matches!(data.main_plant.stage, MainPlantStage::Final) {
             self.garden.swap_textures(
-                ObjectSprite::Frame("front_gardenfucked"),
-                ObjectSprite::Frame("front_gardenfucked_hovered"),
-                sprite_renderer,
+                ObjectSprite::Frame(SpriteId::FrontGardenfucked),
+                ObjectSprite::Frame(SpriteId::FrontGardenfuckedHovered),
             );
             self.garden.position.y = 170.0;
         }
     }
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        self.market.update(context, data);
-        self.garden.update(context, data);
-        self.house.update(context, data);
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
+        self.market.update(dt, data);
+        self.garden.update(dt, data);
+        self.house.update(dt, data);
 
         if !data.player.has_page && self.mainplant_blood {
-            self.page.update(context, data);
+            self.page.update(dt, data);
             if self.page.is_clicked {
                 data.player.has_page = true;
             }
@@ -88,7 +83,7 @@ impl Scene for FrontScene {
                 scale: Vec2::new(RESOLUTION_X as f32, RESOLUTION_Y as f32),
                 ..Default::default()
             },
-            "front_bg",
+            SpriteId::FrontBg,
             0,
         );
 
@@ -5,13 +5,16 @@ use crate::{
     game::{
         clickableobject::ObjectSprite,
         main_plant::MainPlantStage,
+        particles::ParticleEmitter,
         plant::{seed_packet_from_plant, Plant, PlantState, PlantType},
         polygon::Polygon,
+        save::PotSave,
+        sound_pool::SoundPool,
+        sprite_manifest::SpriteId,
         GameData,
     },
 };
 use jandering_engine::{
-    engine::EngineContext,
     object::D2Instance,
     types::{Vec2, DEG_TO_RAD},
 };
@@ -20,8 +23,9 @@ use crate::{
     clickable,
     game::{
         clickableobject::{ClickableObject, ObjectAction},
-        constants::{RESOLUTION_X, RESOLUTION_Y},
-        sprite_renderer::SpriteRenderer,
+        constants::{RESOLUTION_X, RESOLUTION_Y, TEXT_LAYER},
+        sprite_renderer::{SpriteEffects, SpriteRenderer, TextStyle},
+        tween::{Easing, Sequence, Tween},
     },
 };
 
@@ -64,6 +68,8 @@ struct Pots {
 struct Card {
     object: ClickableObject,
     pub plant_type: PlantType,
+    // the spot in the fan the card is sliding to, None when it got moved by hand
+    target: Option<Vec2>,
 }
 
 struct Cards {
@@ -81,36 +87,32 @@ pub struct GardenScene {
     cards: Cards,
     pots: Pots,
     fading_in_before_cut: f32,
+    droplets: ParticleEmitter,
+    blood: ParticleEmitter,
+}
+
+// how held tools and cards settle back into their spot
+fn slide(from: Vec2, to: Vec2) -> Sequence<Vec2> {
+    Tween::new(from, to, 0.75)
+        .with_easing(Easing::CubicOut)
+        .into()
 }
 
 impl GardenScene {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let front = clickable!(0.0, 214.0, "garden_front", sprite_renderer);
-        let mut placeable_pot = clickable!(POT_START.x, POT_START.y, "garden_pot", sprite_renderer);
-        placeable_pot.hovered_sounds = Some(vec![
-            "res/sounds/pot1.mp3",
-            "res/sounds/pot2.mp3",
-            "res/sounds/pot3.mp3",
-        ]);
-        let watering_can =
-            clickable_nohover!(CAN_POS.x, CAN_POS.y, "garden_wateringcan", sprite_renderer);
-        let body_part = clickable_nohover!(BODY_POS.x, BODY_POS.y, "empty", sprite_renderer);
-        let axe = clickable_nohover!(
-            0.0,
-            -0.5 * RESOLUTION_Y as f32,
-            "garden_axe",
-            sprite_renderer
-        );
+    pub fn new() -> Self {
+        let front = clickable!(0.0, 214.0, GardenFront);
+        let mut placeable_pot = clickable!(POT_START.x, POT_START.y, GardenPot);
+        placeable_pot.position_tween = Some(slide(POT_START, POT_END));
+        placeable_pot.hovered_sounds = Some(SoundPool::new("pot_hover"));
+        let watering_can = clickable_nohover!(CAN_POS.x, CAN_POS.y, SpriteId::GardenWateringcan);
+        let body_part = clickable_nohover!(BODY_POS.x, BODY_POS.y, SpriteId::Empty);
+        let axe = clickable_nohover!(0.0, -0.5 * RESOLUTION_Y as f32, SpriteId::GardenAxe);
 
         let mut base_pot = Pot {
-            object: clickable!(0.0, 0.0, "garden_pot", sprite_renderer),
+            object: clickable!(0.0, 0.0, GardenPot),
             plant: None,
         };
-        base_pot.object.hovered_sounds = Some(vec![
-            "res/sounds/pot1.mp3",
-            "res/sounds/pot2.mp3",
-            "res/sounds/pot3.mp3",
-        ]);
+        base_pot.object.hovered_sounds = Some(SoundPool::new("pot_hover"));
         let placeable_area = Polygon {
             points: vec![
                 Vec2 {
@@ -153,36 +155,57 @@ impl GardenScene {
             cards,
             is_final: false,
             fading_in_before_cut: 0.0,
+            droplets: ParticleEmitter::new("water_droplets", Vec2::ZERO),
+            blood: ParticleEmitter::new("blood_splatter", Vec2::ZERO),
         }
     }
 
-    pub fn new_day(&mut self, _data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
+    pub fn new_day(&mut self, _data: &mut GameData) {
+        self.pots.pots.iter_mut().for_each(|pot| pot.grow());
+    }
+
+    pub fn save_pots(&self) -> Vec<PotSave> {
         self.pots
             .pots
-            .iter_mut()
-            .for_each(|pot| pot.grow(sprite_renderer));
+            .iter()
+            .map(|pot| PotSave {
+                position: pot.object.position.to_array(),
+                plant: pot.plant.as_ref().map(|plant| plant.save()),
+            })
+            .collect()
     }
 
-    fn update_body_part(&mut self, sprite_renderer: &mut SpriteRenderer, data: &mut GameData) {
+    pub fn load_pots(&mut self, pots: &[PotSave]) {
+        self.pots.held_pot = None;
+        self.pots.pots = pots
+            .iter()
+            .map(|save| {
+                let mut pot = self.pots.base_pot.clone();
+                pot.object.position = Vec2::from_array(save.position);
+                pot.plant = save.plant.as_ref().map(Plant::restore);
+                pot
+            })
+            .collect();
+    }
+
+    fn update_body_part(&mut self, data: &mut GameData) {
         //body part
         if data.player.cut_finger && !data.player.used_finger {
             self.body_part.swap_textures(
-                ObjectSprite::Frame("garden_finger"),
-                ObjectSprite::Frame("garden_finger"),
-                sprite_renderer,
+                ObjectSprite::Frame(SpriteId::GardenFinger),
+                ObjectSprite::Frame(SpriteId::GardenFinger),
             );
         } else if data.player.cut_eye && !data.player.used_eye {
             self.body_part.swap_textures(
-                ObjectSprite::Frame("garden_eye"),
-                ObjectSprite::Frame("garden_eye"),
-                sprite_renderer,
+                ObjectSprite::Frame(SpriteId::GardenEye),
+                ObjectSprite::Frame(SpriteId::GardenEye),
             );
         }
     }
 }
 
 impl Scene for GardenScene {
-    fn refresh(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
+    fn refresh(&mut self, data: &mut GameData) {
         if matches!(data.main_plant.stage, MainPlantStage::Final) {
             self.pots.die();
             self.cards.cards.clear();
@@ -194,25 +217,28 @@ impl Scene for GardenScene {
         self.cards.cards.clear();
         for (plant_type, _) in data.player.owned_seeds.iter() {
             let plant_type = *plant_type;
-            let mut object = seed_packet_from_plant(plant_type, sprite_renderer);
+            let mut object = seed_packet_from_plant(plant_type);
             object.position.y = CARD_STARTING_Y;
-            self.cards.cards.push(Card { object, plant_type });
+            self.cards.cards.push(Card {
+                object,
+                plant_type,
+                target: None,
+            });
             self.placeable_pot.position = POT_START;
+            self.placeable_pot.position_tween = Some(slide(POT_START, POT_END));
         }
 
         if !self.is_final {
-            self.update_body_part(sprite_renderer, data);
+            self.update_body_part(data);
         }
     }
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
+        self.droplets.update(dt);
+        self.blood.update(dt);
+
         if self.is_final {
             if self.fading_in_before_cut > 0.0 {
-                self.fading_in_before_cut -= context.dt as f32;
+                self.fading_in_before_cut -= dt;
                 data.popr.darkness = 1.0 - self.fading_in_before_cut / 2.0;
                 if self.fading_in_before_cut < 0.0 {
                     data.popr.vignette = 1.0;
@@ -220,7 +246,7 @@ impl Scene for GardenScene {
                 }
             } else if data.player.has_axe {
                 let was_held = self.axe.is_held;
-                self.axe.update(context, data);
+                self.axe.update(dt, data);
                 if self.axe.is_held {
                     self.axe.position = data.input.mouse_pos.unwrap_or(Vec2::ZERO);
                 }
@@ -235,16 +261,16 @@ impl Scene for GardenScene {
         }
 
         if data.popr.darkness > 0.0 {
-            data.popr.darkness -= context.dt as f32 * 3.0;
+            data.popr.darkness -= dt * 3.0;
             return None;
         }
         {
             //please dont judge me for this
             let mut cloned = data.main_plant.object.clone();
-            cloned.update(context, data);
+            cloned.update(dt, data);
             data.main_plant.object = cloned;
         }
-        self.front.update(context, data);
+        self.front.update(dt, data);
         if self.front.is_clicked {
             return Some(ObjectAction::Goto(ActiveScene::Front));
         }
@@ -255,46 +281,53 @@ impl Scene for GardenScene {
 
         //body part
         if !self.is_final {
-            self.body_part.update(context, data);
+            self.body_part.update(dt, data);
             if self.body_part.is_held {
+                self.body_part.position_tween = None;
                 self.body_part.position = data.input.mouse_pos.unwrap_or(Vec2::ZERO);
-            } else {
-                self.body_part.position +=
-                    (BODY_POS - self.body_part.position) * context.dt as f32 * 4.0;
+            } else if was_body_held {
+                self.body_part.position_tween = Some(slide(self.body_part.position, BODY_POS));
             }
             self.body_part.scale = Pots::perspective_factor(self.body_part.position.y);
 
-            self.watering_can.update(context, data);
+            self.watering_can.update(dt, data);
             if self.watering_can.is_held {
+                self.watering_can.position_tween = None;
                 self.watering_can.position = data.input.mouse_pos.unwrap_or(Vec2::ZERO);
-            } else {
-                self.watering_can.position +=
-                    (CAN_POS - self.watering_can.position) * context.dt as f32 * 4.0;
+            } else if was_can_held {
+                self.watering_can.position_tween = Some(slide(self.watering_can.position, CAN_POS));
             }
             self.watering_can.scale = Pots::perspective_factor(self.watering_can.position.y);
 
-            self.placeable_pot.update(context, data);
+            self.placeable_pot.update(dt, data);
             if self.placeable_pot.is_held {
+                self.placeable_pot.position_tween = None;
                 self.placeable_pot.position = data.input.mouse_pos.unwrap_or(Vec2::ZERO);
-            } else {
-                self.placeable_pot.position +=
-                    (POT_END - self.placeable_pot.position) * context.dt as f32 * 4.0;
+            } else if was_pot_held {
+                self.placeable_pot.position_tween =
+                    Some(slide(self.placeable_pot.position, POT_END));
             }
             self.placeable_pot.scale = Pots::perspective_factor(self.placeable_pot.position.y);
+            // see-through while it's somewhere it can't be put down
+            self.placeable_pot.effects = if self.placeable_pot.is_held
+                && !self
+                    .pots
+                    .placeable_area
+                    .point_inside(self.placeable_pot.position)
+            {
+                SpriteEffects::faded(0.5)
+            } else {
+                SpriteEffects::default()
+            };
         }
 
-        self.cards.update(context, data, sprite_renderer);
-        self.pots.update(
-            context,
-            data,
-            self.cards.held_card.is_some(),
-            sprite_renderer,
-        );
+        self.cards.update(dt, data);
+        self.pots.update(dt, data, self.cards.held_card.is_some());
 
         if let Some(card_index) = self.cards.held_card {
             if data.input.left_released {
                 let plant_type = self.cards.cards[card_index].plant_type;
-                if self.pots.place_plant(plant_type, sprite_renderer) {
+                if self.pots.place_plant(plant_type) {
                     let n_seeds = data.player.owned_seeds.get_mut(&plant_type).unwrap();
                     if *n_seeds == 1 {
                         data.player.owned_seeds.remove(&plant_type);
@@ -334,13 +367,22 @@ impl Scene for GardenScene {
             if data.input.left_released {
                 if was_pot_held && self.pots.try_placing_pot(data) {
                     self.placeable_pot.position = POT_START;
+                    self.placeable_pot.position_tween = Some(slide(POT_START, POT_END));
                 }
                 if was_can_held {
-                    self.pots.water();
+                    if let Some(position) = self.pots.water() {
+                        self.droplets.burst_at(position, data.effects_rng);
+                    }
                 }
                 if was_body_held {
+                    let was_hungry = data.main_plant.requires_blood;
                     data.main_plant.feed(data.player, data.popr);
-                    self.update_body_part(sprite_renderer, data);
+                    if was_hungry && !data.main_plant.requires_blood {
+                        self.blood
+                            .burst_at(self.body_part.position, data.effects_rng);
+                        data.camera.add_trauma(0.3);
+                    }
+                    self.update_body_part(data);
                 }
             }
         }
@@ -354,7 +396,7 @@ impl Scene for GardenScene {
                 scale: Vec2::new(RESOLUTION_X as f32, RESOLUTION_Y as f32),
                 ..Default::default()
             },
-            "garden_bg",
+            SpriteId::GardenBg,
             0,
         );
 
@@ -367,33 +409,51 @@ impl Scene for GardenScene {
             if (data.player.cut_finger && !data.player.used_finger)
                 || (data.player.cut_eye && !data.player.used_eye)
             {
-                self.body_part.render(sprite_renderer);
+                self.body_part
+                    .render_interpolated(sprite_renderer, data.alpha);
             }
-            self.watering_can.render(sprite_renderer);
+            self.watering_can
+                .render_interpolated(sprite_renderer, data.alpha);
             if data.player.owned_pots > 0 {
-                self.placeable_pot.render(sprite_renderer);
+                self.placeable_pot
+                    .render_interpolated(sprite_renderer, data.alpha);
             }
         }
 
         self.pots.render(sprite_renderer);
         self.front.render(sprite_renderer);
-        self.cards.render(sprite_renderer);
+        self.cards.render(sprite_renderer, data.alpha);
+        self.droplets.render(sprite_renderer, data.alpha);
+        self.blood.render(sprite_renderer, data.alpha);
+
+        let day = format!("Day {}", data.player.days + 1);
+        let style = TextStyle::default();
+        let text_size = sprite_renderer.measure_text(&day, &style);
+        let text_position = Vec2::new(
+            RESOLUTION_X as f32 * -0.5 + 32.0,
+            RESOLUTION_Y as f32 * 0.5 - 24.0,
+        );
+        sprite_renderer.render_nine_slice(
+            D2Instance {
+                position: text_position + Vec2::new(text_size.x, -text_size.y) * 0.5,
+                ..Default::default()
+            },
+            SpriteId::UiPanel,
+            TEXT_LAYER - 1,
+            text_size + Vec2::splat(32.0),
+            SpriteEffects::default(),
+        );
+        sprite_renderer.render_text(&day, text_position, &style, TEXT_LAYER);
     }
 }
 
 impl Pots {
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        data: &mut GameData,
-        is_holding_card: bool,
-        sprite_renderer: &mut SpriteRenderer,
-    ) {
+    fn update(&mut self, dt: f32, data: &mut GameData, is_holding_card: bool) {
         let mouse_pos = data.input.mouse_pos.unwrap_or(Vec2::ZERO);
 
         let mut is_pot_hovered = false;
         self.pots.iter_mut().enumerate().for_each(|(i, pot)| {
-            pot.object.update(context, data);
+            pot.object.update(dt, data);
 
             if pot.object.is_held && self.held_pot.is_none() {
                 self.held_pot = Some((i, pot.object.position - mouse_pos));
@@ -428,13 +488,13 @@ impl Pots {
         for pot in self.pots.iter_mut() {
             let center = pot.center();
             if let Some(plant) = &mut pot.plant {
-                plant.update(sprite_renderer);
+                plant.update();
                 plant.object.scale = pot.object.scale;
                 plant.object.position = center;
                 plant.object.position.y += plant.object.size.y * 0.5;
-                plant.object.update(context, data);
+                plant.object.update(dt, data);
                 plant.object.position = center;
-                plant.object.position.y += plant.object.size(sprite_renderer).y * 0.5;
+                plant.object.position.y += plant.object.size().y * 0.5;
             }
         }
     }
@@ -450,7 +510,7 @@ impl Pots {
         });
     }
 
-    fn place_plant(&mut self, plant_type: PlantType, sprite_renderer: &mut SpriteRenderer) -> bool {
+    fn place_plant(&mut self, plant_type: PlantType) -> bool {
         if let Some(pot) = self
             .pots
             .iter_mut()
@@ -465,7 +525,7 @@ impl Pots {
                 }
             })
         {
-            pot.plant = Some(Plant::new(plant_type, sprite_renderer));
+            pot.plant = Some(Plant::new(plant_type));
             true
         } else {
             false
@@ -528,12 +588,14 @@ impl Pots {
         }
     }
 
-    fn water(&mut self) {
-        if let Some(pot) = self.pots.iter_mut().find(|pot| {
+    // where the watered plant is, if the can was over a thirsty one
+    fn water(&mut self) -> Option<Vec2> {
+        let pot = self.pots.iter_mut().find(|pot| {
             pot.plant.as_ref().is_some_and(|plant| !plant.watered) && pot.object.is_hovered
-        }) {
-            pot.plant.as_mut().unwrap().watered = true;
-        }
+        })?;
+        let plant = pot.plant.as_mut().unwrap();
+        plant.watered = true;
+        Some(plant.object.position)
     }
 }
 
@@ -542,7 +604,7 @@ impl Pot {
         self.object.position + Vec2::new(0.0, self.object.size.y * 0.5 * 0.6 * self.object.scale)
     }
 
-    fn grow(&mut self, _sprite_renderer: &mut SpriteRenderer) {
+    fn grow(&mut self) {
         if let Some(plant) = &mut self.plant {
             plant.grow();
         }
@@ -550,12 +612,7 @@ impl Pot {
 }
 
 impl Cards {
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        data: &mut GameData,
-        _sprite_renderer: &mut SpriteRenderer,
-    ) {
+    fn update(&mut self, dt: f32, data: &mut GameData) {
         let n_cards = self.cards.len();
         let angle = (n_cards as f32 - 1.0) * 90.0;
         let starting_pos = Vec2::new(0.0, RESOLUTION_Y as f32 / -2.0 + 50.0);
@@ -571,10 +628,13 @@ impl Cards {
             let angle = ratio * angle;
             let angle_rad = (offset_angle + angle) * DEG_TO_RAD;
             let target_pos = starting_pos + Vec2::from_angle(angle_rad) * 60.0;
-            card.object.position += (target_pos - card.object.position) * context.dt as f32 * 3.0;
+            if card.target != Some(target_pos) {
+                card.object.position_tween = Some(slide(card.object.position, target_pos));
+                card.target = Some(target_pos);
+            }
+            card.object.update(dt, data);
             card.object.rotation =
                 (Vec2::new(0.0, CARD_STARTING_Y) - card.object.position).to_angle() + PI * 0.5;
-            card.object.update(context, data);
 
             if card.object.is_clicked && self.held_card.is_none() {
                 data.input.left_pressed = false;
@@ -585,12 +645,14 @@ impl Cards {
             let card = &mut self.cards[card_index];
             card.object.position = data.input.mouse_pos.unwrap_or(Vec2::ZERO);
             card.object.rotation = 0.0;
+            card.object.position_tween = None;
+            card.target = None;
         }
     }
 
-    fn render(&mut self, sprite_renderer: &mut SpriteRenderer) {
+    fn render(&mut self, sprite_renderer: &mut SpriteRenderer, alpha: f32) {
         for card in self.cards.iter() {
-            card.object.render(sprite_renderer);
+            card.object.render_interpolated(sprite_renderer, alpha);
         }
     }
 }
@@ -1,15 +1,17 @@
 use crate::game::clickableobject::ObjectSprite;
 use crate::game::constants::SLEEP_LENGTH;
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::{
-    clickable,
+    clickable, clickable_fullanim,
     game::{
         clickableobject::{ClickableObject, ObjectAction},
         constants::{RESOLUTION_X, RESOLUTION_Y},
         sprite_renderer::SpriteRenderer,
+        tween::{Sequence, Tween},
     },
 };
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 
 use super::{ActiveScene, Scene};
 
@@ -17,51 +19,47 @@ pub struct HouseScene {
     door: ClickableObject,
     bed: ClickableObject,
     table: ClickableObject,
-    sleep_timer: f32,
+    guy: ClickableObject,
+    sleep: Option<Sequence<f32>>,
 }
 
 impl HouseScene {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let door = clickable!(-336.0, 118.0, "house_door", sprite_renderer);
-        let bed = clickable!(375.0, -85.0, "house_bed", sprite_renderer);
-        let table = clickable!(-422.0, -160.0, "house_table", sprite_renderer);
+    pub fn new() -> Self {
+        let door = clickable!(-336.0, 118.0, HouseDoor);
+        let bed = clickable!(375.0, -85.0, HouseBed);
+        let table = clickable!(-422.0, -160.0, HouseTable);
+        let guy = clickable_fullanim!(40.0, -40.0, "house_guy_idle", "house_guy_wave");
         Self {
             door,
             bed,
             table,
-            sleep_timer: 0.0,
+            guy,
+            sleep: None,
         }
     }
 }
 
 impl Scene for HouseScene {
-    fn refresh(&mut self, _data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {}
+    fn refresh(&mut self, _data: &mut GameData) {}
 
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        if self.sleep_timer > 0.0 {
-            let over_half = self.sleep_timer > SLEEP_LENGTH * 0.5;
-            self.sleep_timer -= context.dt as f32;
-
-            if self.sleep_timer <= 0.0 {
-                data.popr.darkness = 0.0;
-            } else {
-                data.popr.darkness = 1.0 - (0.5 - self.sleep_timer / SLEEP_LENGTH).abs() * 2.0;
-                if over_half && self.sleep_timer <= SLEEP_LENGTH * 0.5 {
-                    return Some(ObjectAction::NewDay);
-                }
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
+        if let Some(sleep) = &mut self.sleep {
+            let events = sleep.update(dt);
+            data.popr.darkness = sleep.value();
+            if sleep.is_finished() {
+                self.sleep = None;
+            }
+            if events.contains(&"new_day") {
+                return Some(ObjectAction::NewDay);
             }
 
             return None;
         }
 
-        self.door.update(context, data);
-        self.bed.update(context, data);
-        self.table.update(context, data);
+        self.door.update(dt, data);
+        self.bed.update(dt, data);
+        self.table.update(dt, data);
+        self.guy.update(dt, data);
         if self.door.is_clicked {
             return Some(ObjectAction::Goto(ActiveScene::Front));
         }
@@ -69,7 +67,11 @@ impl Scene for HouseScene {
             return Some(ObjectAction::Goto(ActiveScene::Table));
         }
         if self.bed.is_clicked {
-            self.sleep_timer = SLEEP_LENGTH;
+            self.sleep = Some(
+                Tween::new(0.0, 1.0, SLEEP_LENGTH * 0.5)
+                    .with_event("new_day")
+                    .then(Tween::new(1.0, 0.0, SLEEP_LENGTH * 0.5)),
+            );
         }
 
         None
@@ -81,12 +83,13 @@ impl Scene for HouseScene {
                 scale: Vec2::new(RESOLUTION_X as f32, RESOLUTION_Y as f32),
                 ..Default::default()
             },
-            "house_bg",
+            SpriteId::HouseBg,
             0,
         );
 
         self.door.render(sprite_renderer);
         self.bed.render(sprite_renderer);
         self.table.render(sprite_renderer);
+        self.guy.render(sprite_renderer);
     }
 }
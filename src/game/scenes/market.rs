@@ -1,18 +1,21 @@
 use crate::game::clickableobject::ObjectSprite;
 use crate::game::constants::{
-    AXE_PRICE, FLOWER_PRICE, POT_PRICE, RESOLUTION_Y, STRAWBERRY_PRICE, WATERMELON_PRICE,
+    AXE_PRICE, FLOWER_PRICE, POT_PRICE, RESOLUTION_Y, STRAWBERRY_PRICE, TEXT_LAYER,
+    WATERMELON_PRICE,
 };
 use crate::game::main_plant::MainPlantStage;
 use crate::game::plant::PlantType;
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::{
     clickable,
     game::{
         clickableobject::{ClickableObject, ObjectAction},
-        sprite_renderer::SpriteRenderer,
+        sprite_renderer::{SpriteRenderer, TextAlign, TextStyle},
+        tween::{Easing, Tween},
     },
 };
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 
 use super::{ActiveScene, Scene};
 
@@ -34,31 +37,31 @@ pub struct MarketScene {
 }
 
 impl MarketScene {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let front = clickable!(-32.0, -256.0, "market_front", sprite_renderer);
+    pub fn new() -> Self {
+        let front = clickable!(-32.0, -256.0, MarketFront);
         let packets = vec![
             SeedPacket {
-                object: clickable!(-100.0, 164.0, "market_seeds_flower", sprite_renderer),
+                object: clickable!(-100.0, 164.0, MarketSeedsFlower),
                 plant_type: PlantType::Flower,
                 cost: FLOWER_PRICE,
                 starting_y: 164.0,
             },
             SeedPacket {
-                object: clickable!(107.0, 170.0, "market_seeds_strawberry", sprite_renderer),
+                object: clickable!(107.0, 170.0, MarketSeedsStrawberry),
                 plant_type: PlantType::Strawberry,
                 cost: STRAWBERRY_PRICE,
                 starting_y: 170.0,
             },
             SeedPacket {
-                object: clickable!(314.0, 160.0, "market_seeds_watermelon", sprite_renderer),
+                object: clickable!(314.0, 160.0, MarketSeedsWatermelon),
                 plant_type: PlantType::Watermelon,
                 cost: WATERMELON_PRICE,
                 starting_y: 160.0,
             },
         ];
 
-        let pot = clickable!(-120.0, -130.0, "market_pot", sprite_renderer);
-        let holy_axe = clickable!(180.0, -163.0, "market_holyaxe", sprite_renderer);
+        let pot = clickable!(-120.0, -130.0, MarketPot);
+        let holy_axe = clickable!(180.0, -163.0, MarketHolyaxe);
 
         Self {
             front,
@@ -71,22 +74,17 @@ impl MarketScene {
 }
 
 impl Scene for MarketScene {
-    fn refresh(&mut self, _data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {
+    fn refresh(&mut self, _data: &mut GameData) {
         self.time = 0.0;
     }
 
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        self.time += context.dt as f32;
-        self.front.update(context, data);
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
+        self.time += dt;
+        self.front.update(dt, data);
         if self.front.is_clicked {
             return Some(ObjectAction::Goto(ActiveScene::Front));
         }
-        self.pot.update(context, data);
+        self.pot.update(dt, data);
         if self.pot.is_clicked {
             if data.player.coins >= POT_PRICE {
                 data.player.coins -= POT_PRICE;
@@ -102,7 +100,7 @@ impl Scene for MarketScene {
                 | MainPlantStage::Gone
                 | MainPlantStage::Final
         ) {
-            self.holy_axe.update(context, data);
+            self.holy_axe.update(dt, data);
             if self.holy_axe.is_clicked {
                 if data.player.coins >= AXE_PRICE {
                     data.player.coins -= AXE_PRICE;
@@ -113,7 +111,8 @@ impl Scene for MarketScene {
         }
 
         self.packets.iter_mut().for_each(|packet| {
-            packet.object.update(context, data);
+            let was_hovered = packet.object.is_hovered;
+            packet.object.update(dt, data);
 
             if packet.object.is_clicked {
                 if data.player.coins >= packet.cost {
@@ -128,20 +127,26 @@ impl Scene for MarketScene {
                 }
             }
 
-            let target_height = if packet.object.is_hovered {
-                packet.starting_y + 15.0
-            } else {
-                packet.starting_y
-            };
-            packet.object.position.y +=
-                (target_height - packet.object.position.y) * context.dt as f32 * 10.0;
+            if packet.object.is_hovered != was_hovered {
+                let target_height = if packet.object.is_hovered {
+                    packet.starting_y + 15.0
+                } else {
+                    packet.starting_y
+                };
+                let position = packet.object.position;
+                packet.object.position_tween = Some(
+                    Tween::new(position, Vec2::new(position.x, target_height), 0.3)
+                        .with_easing(Easing::CubicOut)
+                        .into(),
+                );
+            }
         });
 
         None
     }
 
     fn render(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
-        sprite_renderer.render(D2Instance::default(), "market_bg", 0);
+        sprite_renderer.render(D2Instance::default(), SpriteId::MarketBg, 0);
 
         (0..data.player.coins).for_each(|i| {
             let index_in_stack = i % 25;
@@ -153,22 +158,39 @@ impl Scene for MarketScene {
                 2.. => (480.0, -157.0),
             };
 
-            let t = (self.time - index_in_stack as f32 * 0.1).clamp(0.0, 1.0);
-            let starting_pos = RESOLUTION_Y as f32 * 0.5 + 100.0;
-            let target_pos = y + index_in_stack as f32 * 20.0;
-            let new_pos = starting_pos + (target_pos - starting_pos) * t.powf(2.5);
+            let new_pos = Tween::new(
+                RESOLUTION_Y as f32 * 0.5 + 100.0,
+                y + index_in_stack as f32 * 20.0,
+                1.0,
+            )
+            .with_delay(index_in_stack as f32 * 0.1)
+            .with_easing(Easing::PowIn(2.5))
+            .value_at(self.time);
             sprite_renderer.render(
                 D2Instance {
                     position: Vec2::new(x, new_pos),
                     ..Default::default()
                 },
-                "market_coin",
+                SpriteId::MarketCoin,
                 (2 - stack) * 25 + i + 2,
             )
         });
 
+        sprite_renderer.render_text(
+            &data.player.coins.to_string(),
+            Vec2::new(505.0, -240.0),
+            &TextStyle {
+                align: TextAlign::Center,
+                ..Default::default()
+            },
+            TEXT_LAYER,
+        );
+
         for packet in self.packets.iter() {
-            packet.object.render(sprite_renderer);
+            packet
+                .object
+                .render_interpolated(sprite_renderer, data.alpha);
+            render_price(data, sprite_renderer, &packet.object, packet.cost);
         }
 
         if matches!(
@@ -180,6 +202,7 @@ impl Scene for MarketScene {
         ) {
             if !data.player.has_axe {
                 self.holy_axe.render(sprite_renderer);
+                render_price(data, sprite_renderer, &self.holy_axe, AXE_PRICE);
             }
         } else {
             sprite_renderer.render(
@@ -187,12 +210,38 @@ impl Scene for MarketScene {
                     position: Vec2::new(-393.0, 84.0),
                     ..Default::default()
                 },
-                "market_shopkeep",
+                SpriteId::MarketShopkeep,
                 1,
             );
         }
 
         self.pot.render(sprite_renderer);
+        render_price(data, sprite_renderer, &self.pot, POT_PRICE);
         self.front.render(sprite_renderer);
     }
 }
+
+// centered under the object where it's drawn, red while it can't be afforded
+fn render_price(
+    data: &GameData,
+    sprite_renderer: &mut SpriteRenderer,
+    object: &ClickableObject,
+    price: u32,
+) {
+    let color = if data.player.coins >= price {
+        [1.0, 1.0, 1.0, 1.0]
+    } else {
+        [0.85, 0.2, 0.2, 1.0]
+    };
+    sprite_renderer.render_text(
+        &price.to_string(),
+        object.interpolated_position(data.alpha) - Vec2::new(0.0, object.size().y * 0.5 + 4.0),
+        &TextStyle {
+            size: 24.0,
+            align: TextAlign::Center,
+            color,
+            ..Default::default()
+        },
+        TEXT_LAYER,
+    );
+}
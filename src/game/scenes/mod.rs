@@ -4,10 +4,12 @@ use self::{
     strawberry_minigame::StrawberryMinigameScene, table::TableScene, title::TitleScene,
     watermelon_minigame::WatermelonMinigameScene,
 };
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
+use serde::{Deserialize, Serialize};
 
 use super::{
-    clickableobject::ObjectAction, constants::SKIP_INTRO, sprite_renderer::SpriteRenderer, GameData,
+    clickableobject::ObjectAction, constants::SKIP_INTRO, music::Mood, sprite_manifest::SpriteId,
+    sprite_renderer::SpriteRenderer, GameData,
 };
 
 pub mod cutting;
@@ -22,7 +24,7 @@ pub mod table;
 pub mod title;
 pub mod watermelon_minigame;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum ActiveScene {
     House,
     Front,
@@ -37,6 +39,18 @@ pub enum ActiveScene {
     Dying,
 }
 
+impl ActiveScene {
+    pub fn mood(self) -> Mood {
+        match self {
+            ActiveScene::FlowerMinigame
+            | ActiveScene::StrawberryMinigame
+            | ActiveScene::WatermelonMinigame => Mood::Playful,
+            ActiveScene::Cutting | ActiveScene::Dying => Mood::Dread,
+            _ => Mood::Calm,
+        }
+    }
+}
+
 pub struct Scenes {
     pub front: FrontScene,
     pub house: HouseScene,
@@ -54,18 +68,18 @@ pub struct Scenes {
 }
 
 impl Scenes {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let house = HouseScene::new(sprite_renderer);
-        let front = FrontScene::new(sprite_renderer);
-        let garden = GardenScene::new(sprite_renderer);
-        let market = MarketScene::new(sprite_renderer);
-        let title = TitleScene::new(sprite_renderer);
-        let table = TableScene::new(sprite_renderer);
-        let cutting = CuttingScene::new(sprite_renderer);
-        let flower_minigame = FlowerMinigameScene::new(sprite_renderer);
-        let strawberry_minigame = StrawberryMinigameScene::new(sprite_renderer);
-        let watermelon_minigame = WatermelonMinigameScene::new(sprite_renderer);
-        let dying = DyingScene::new(sprite_renderer);
+    pub fn new() -> Self {
+        let house = HouseScene::new();
+        let front = FrontScene::new();
+        let garden = GardenScene::new();
+        let market = MarketScene::new();
+        let title = TitleScene::new();
+        let table = TableScene::new();
+        let cutting = CuttingScene::new();
+        let flower_minigame = FlowerMinigameScene::new();
+        let strawberry_minigame = StrawberryMinigameScene::new();
+        let watermelon_minigame = WatermelonMinigameScene::new();
+        let dying = DyingScene::new();
 
         Scenes {
             house,
@@ -109,39 +123,36 @@ impl Scenes {
 }
 
 pub trait Scene {
-    fn refresh(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer);
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction>;
+    fn refresh(&mut self, data: &mut GameData);
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction>;
 
     fn render(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer);
 }
 
 pub struct MinigameFingers {
     pub pos: Vec2,
+    pub prev_pos: Vec2,
     pub vel_x: f32,
 }
 
 impl MinigameFingers {
-    pub fn update(&mut self, data: &mut GameData, context: &EngineContext) {
+    pub fn update(&mut self, data: &mut GameData, dt: f32) {
+        self.prev_pos = self.pos;
         self.pos.x += (data.input.mouse_pos.unwrap_or(Vec2::ZERO).x - self.pos.x)
-            * context.dt as f32
+            * dt
             * if data.player.cut_finger { 0.75 } else { 1.0 };
     }
 
     pub fn render(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
         sprite_renderer.render(
             D2Instance {
-                position: self.pos,
+                position: self.prev_pos.lerp(self.pos, data.alpha),
                 ..Default::default()
             },
             if data.player.cut_finger {
-                "garden_fingersmissing"
+                SpriteId::GardenFingersmissing
             } else {
-                "garden_fingers"
+                SpriteId::GardenFingers
             },
             10,
         )
@@ -1,13 +1,16 @@
 use crate::game::constants::{RESOLUTION_X, RESOLUTION_Y};
+use crate::game::particles::ParticleEmitter;
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::game::{clickableobject::ObjectAction, sprite_renderer::SpriteRenderer};
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 use rand::Rng;
 
 use super::{MinigameFingers, Scene};
 
 struct FallingObject {
     pos: Vec2,
+    prev_pos: Vec2,
     velocity: Vec2,
     rotation: f32,
     collected: bool,
@@ -17,48 +20,49 @@ pub struct StrawberryMinigameScene {
     falling_objects: Vec<FallingObject>,
     time: f32,
     fingers: MinigameFingers,
+    sparkles: ParticleEmitter,
 }
 
 impl StrawberryMinigameScene {
-    pub fn new(_sprite_renderer: &mut SpriteRenderer) -> Self {
+    pub fn new() -> Self {
         let fingers = MinigameFingers {
             pos: Vec2::new(0.0, RESOLUTION_Y as f32 * -0.5 * 0.9),
+            prev_pos: Vec2::new(0.0, RESOLUTION_Y as f32 * -0.5 * 0.9),
             vel_x: 0.0,
         };
         Self {
             falling_objects: Vec::new(),
             time: 0.0,
             fingers,
+            sparkles: ParticleEmitter::new("coin_sparkle", Vec2::ZERO),
         }
     }
 }
 
 impl Scene for StrawberryMinigameScene {
-    fn refresh(&mut self, data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {
+    fn refresh(&mut self, data: &mut GameData) {
         self.falling_objects.clear();
+        self.sparkles.clear();
         self.fingers.pos.x = 0.0;
+        self.fingers.prev_pos = self.fingers.pos;
         self.time = 0.0;
         (1..18).for_each(|i| {
             let x = (data.rng.gen::<f32>() - 0.5) * 0.5 * RESOLUTION_X as f32;
             let pos = Vec2::new(x, RESOLUTION_Y as f32 * 0.5 + i as f32 * 300.0);
             self.falling_objects.push(FallingObject {
                 pos,
+                prev_pos: pos,
                 rotation: 0.0,
                 velocity: Vec2::ZERO,
                 collected: false,
             })
         })
     }
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        let dt = context.dt as f32;
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
         self.time += dt;
 
-        self.fingers.update(data, context);
+        self.fingers.update(data, dt);
+        self.sparkles.update(dt);
 
         self.falling_objects.iter_mut().for_each(|e| {
             if e.collected {
@@ -66,6 +70,7 @@ impl Scene for StrawberryMinigameScene {
             } else {
                 if self.fingers.collides(data, e.pos) {
                     e.collected = true;
+                    self.sparkles.burst_at(e.pos, data.effects_rng);
                     data.player.coins += 1;
                     e.velocity.y = 100.0;
                     e.velocity.x = 0.0;
@@ -83,9 +88,10 @@ impl Scene for StrawberryMinigameScene {
             return Some(ObjectAction::Goto(super::ActiveScene::Garden));
         }
 
-        self.falling_objects
-            .iter_mut()
-            .for_each(|e| e.pos += e.velocity * dt);
+        self.falling_objects.iter_mut().for_each(|e| {
+            e.prev_pos = e.pos;
+            e.pos += e.velocity * dt;
+        });
 
         None
     }
@@ -96,7 +102,7 @@ impl Scene for StrawberryMinigameScene {
                 scale: Vec2::new(RESOLUTION_X as f32, RESOLUTION_Y as f32),
                 ..Default::default()
             },
-            "garden_bg",
+            SpriteId::GardenBg,
             0,
         );
 
@@ -105,17 +111,19 @@ impl Scene for StrawberryMinigameScene {
         self.falling_objects.iter().for_each(|e| {
             sprite_renderer.render(
                 D2Instance {
-                    position: e.pos,
+                    position: e.prev_pos.lerp(e.pos, data.alpha),
                     rotation: e.rotation,
                     ..Default::default()
                 },
                 if e.collected {
-                    "market_coin"
+                    SpriteId::MarketCoin
                 } else {
-                    "garden_falling_strawberry"
+                    SpriteId::GardenFallingStrawberry
                 },
                 2,
             )
-        })
+        });
+
+        self.sparkles.render(sprite_renderer, data.alpha);
     }
 }
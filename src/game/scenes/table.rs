@@ -2,10 +2,11 @@ use crate::game::clickableobject::ClickableObject;
 use crate::game::GameData;
 use crate::game::{
     clickableobject::{ObjectAction, ObjectSprite},
+    sprite_manifest::SpriteId,
     sprite_renderer::SpriteRenderer,
 };
 use crate::{clickable, clickable_nohover};
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 
 use super::Scene;
 
@@ -24,12 +25,12 @@ pub struct TableScene {
 }
 
 impl TableScene {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let book = clickable!(-340.0, -50.0, "table_closedbook", sprite_renderer);
-        let plate = clickable_nohover!(70.0, -110.0, "table_plate", sprite_renderer);
-        let knife = clickable!(284.0, -118.0, "table_knife", sprite_renderer);
-        let spoon = clickable!(353.0, -120.0, "table_spoon", sprite_renderer);
-        let home = clickable!(-160.0, -260.0, "table_home", sprite_renderer);
+    pub fn new() -> Self {
+        let book = clickable!(-340.0, -50.0, TableClosedbook);
+        let plate = clickable_nohover!(70.0, -110.0, SpriteId::TablePlate);
+        let knife = clickable!(284.0, -118.0, TableKnife);
+        let spoon = clickable!(353.0, -120.0, TableSpoon);
+        let home = clickable!(-160.0, -260.0, TableHome);
         Self {
             book,
             knife,
@@ -45,32 +46,27 @@ impl TableScene {
 }
 
 impl Scene for TableScene {
-    fn refresh(&mut self, data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {
+    fn refresh(&mut self, data: &mut GameData) {
         self.mainplant_hungry = data.main_plant.requires_blood;
     }
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
         if self.timer > 0.0 {
             let over_half = self.timer > 1.5;
-            self.timer -= context.dt as f32;
+            self.timer -= dt;
             if self.timer < 0.0 {
                 data.popr.distortion = self.pre_timer_distortion;
             } else if over_half && self.timer < 1.5 {
                 if self.knife.is_clicked {
-                    self.knife.texture = ObjectSprite::Frame("table_knife_blood");
+                    self.knife.texture = ObjectSprite::Frame(SpriteId::TableKnifeBlood);
                     data.player.cut_finger = true;
                 }
                 if self.spoon.is_clicked {
-                    self.spoon.texture = ObjectSprite::Frame("table_spoon_blood");
+                    self.spoon.texture = ObjectSprite::Frame(SpriteId::TableSpoonBlood);
                     data.player.cut_eye = true;
                 }
             } else if self.spoon.is_clicked {
                 if self.timer > 1.75 {
-                    data.popr.distortion += context.dt as f32 * 7.0;
+                    data.popr.distortion += dt * 7.0;
                 }
                 if (self.timer - 1.5).abs() < 0.25 {
                     data.popr.darkness = 1.0;
@@ -79,7 +75,7 @@ impl Scene for TableScene {
                     data.popr.darkness = 0.0;
                 }
             } else {
-                data.popr.distortion += context.dt as f32 * 3.0;
+                data.popr.distortion += dt * 3.0;
             }
 
             return None;
@@ -94,9 +90,9 @@ impl Scene for TableScene {
                 data.input.left_pressed = false;
             }
         } else {
-            self.book.update(context, data);
-            self.home.update(context, data);
-            self.plate.update(context, data);
+            self.book.update(dt, data);
+            self.home.update(dt, data);
+            self.plate.update(dt, data);
             if self.book.is_clicked {
                 self.book_opened = true;
             }
@@ -106,10 +102,10 @@ impl Scene for TableScene {
 
             if self.mainplant_hungry {
                 if !data.player.cut_finger {
-                    self.knife.update(context, data);
+                    self.knife.update(dt, data);
                 }
                 if !data.player.cut_eye {
-                    self.spoon.update(context, data);
+                    self.spoon.update(dt, data);
                 }
 
                 if data.player.cut_finger {
@@ -137,13 +133,13 @@ impl Scene for TableScene {
     }
 
     fn render(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
-        sprite_renderer.render(D2Instance::default(), "table_bg", 0);
+        sprite_renderer.render(D2Instance::default(), SpriteId::TableBg, 0);
 
         if self.book_opened {
             if data.player.has_page {
-                sprite_renderer.render(D2Instance::default(), "table_book_fixed", 1);
+                sprite_renderer.render(D2Instance::default(), SpriteId::TableBookFixed, 1);
             } else {
-                sprite_renderer.render(D2Instance::default(), "table_book", 1);
+                sprite_renderer.render(D2Instance::default(), SpriteId::TableBook, 1);
             }
         } else {
             self.book.render(sprite_renderer);
@@ -1,83 +1,211 @@
 use crate::game::clickableobject::ClickableObject;
-use crate::game::constants::RESOLUTION_Y;
+use crate::game::constants::{RESOLUTION_Y, SAVE_SLOTS, TEXT_LAYER};
+use crate::game::save::{self, SlotSummary};
 use crate::game::GameData;
 use crate::game::{
     clickableobject::{ObjectAction, ObjectSprite},
-    sprite_renderer::SpriteRenderer,
+    sprite_manifest::SpriteId,
+    sprite_renderer::{SpriteRenderer, TextAlign, TextStyle},
+    tween::{Easing, Tween},
 };
 use crate::{clickable, clickable_nohover};
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 
 use super::Scene;
 
+const SLOT_SPACING: f32 = 380.0;
+const DELETE_OFFSET: Vec2 = Vec2::new(112.0, 58.0);
+// the same ink the slot is drawn with
+const SUMMARY_COLOR: [f32; 4] = [0.23, 0.22, 0.215, 1.0];
+
 enum State {
     Idle,
-    PlayAnim { play_anim_timer: f32, vel_y: f32 },
-    Intro(f32),
+    PlayAnim {
+        play_anim_timer: f32,
+        vel_y: f32,
+        action: ObjectAction,
+    },
+    // waits for a click before the background starts sliding in
+    Intro(Option<Tween<f32>>),
+}
+
+struct SaveSlot {
+    object: ClickableObject,
+    new_btn: ClickableObject,
+    delete_btn: ClickableObject,
+    summary: Option<SlotSummary>,
 }
 
 pub struct TitleScene {
-    play_btn: ClickableObject,
+    slots: Vec<SaveSlot>,
     sound_toggle: ClickableObject,
     state: State,
     bg_y: f32,
 }
 
 impl TitleScene {
-    pub fn new(sprite_renderer: &mut SpriteRenderer) -> Self {
-        let play_btn = clickable!(0.0, 0.0, "title_play", sprite_renderer);
-        let sound_toggle = clickable_nohover!(0.0, -0.0, "title_sound_on", sprite_renderer);
+    pub fn new() -> Self {
+        let slots = (0..SAVE_SLOTS)
+            .map(|i| {
+                let x = (i as f32 - (SAVE_SLOTS as f32 - 1.0) * 0.5) * SLOT_SPACING;
+                SaveSlot {
+                    object: clickable!(x, 0.0, TitleSlot),
+                    new_btn: clickable!(x, 0.0, TitleNew),
+                    delete_btn: ClickableObject::with_label(
+                        Vec2::new(x, 0.0),
+                        SpriteId::UiPanel,
+                        "delete",
+                        TextStyle {
+                            size: 22.0,
+                            ..Default::default()
+                        },
+                    ),
+                    summary: None,
+                }
+            })
+            .collect();
+        let sound_toggle = clickable_nohover!(0.0, -0.0, SpriteId::TitleSoundOn);
         Self {
-            play_btn,
+            slots,
             sound_toggle,
-            state: State::Intro(-1.0),
+            state: State::Intro(None),
             bg_y: 0.0,
         }
     }
 }
 
-impl Scene for TitleScene {
-    fn refresh(&mut self, _data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {}
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        let dt = context.dt as f32;
+impl SaveSlot {
+    fn update(&mut self, index: usize, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
+        if self.summary.is_some() {
+            self.delete_btn.update(dt, data);
+            if self.delete_btn.is_clicked {
+                save::clear(data.storage, index);
+                self.summary = None;
+                data.input.left_pressed = false;
+            }
+        }
+
+        self.object.update(dt, data);
+        self.new_btn.is_hovered = self.object.is_hovered;
+
+        if !self.object.is_clicked {
+            return None;
+        }
+
+        if self.summary.is_some() {
+            Some(ObjectAction::LoadSlot(index))
+        } else {
+            Some(ObjectAction::NewGame(index))
+        }
+    }
+
+    fn set_y(&mut self, y: f32) {
+        self.object.position.y = y;
+        self.new_btn.position.y = y;
+        self.delete_btn.position = Vec2::new(self.object.position.x, y) + DELETE_OFFSET;
+    }
+
+    fn render(&self, sprite_renderer: &mut SpriteRenderer) {
+        self.object.render(sprite_renderer);
+
+        let summary = match &self.summary {
+            Some(summary) => summary,
+            None => {
+                self.new_btn.render(sprite_renderer);
+                return;
+            }
+        };
+
+        self.delete_btn.render(sprite_renderer);
+
+        let position = self.object.position;
+        sprite_renderer.render_with_scale(
+            D2Instance {
+                position: position + Vec2::new(-70.0, 15.0),
+                ..Default::default()
+            },
+            summary.stage.sprite(),
+            2,
+            0.12,
+        );
+
+        sprite_renderer.render(
+            D2Instance {
+                position: position + Vec2::new(45.0, 20.0),
+                ..Default::default()
+            },
+            SpriteId::GardenCoin,
+            2,
+        );
+        let style = TextStyle {
+            color: SUMMARY_COLOR,
+            ..Default::default()
+        };
+        sprite_renderer.render_text(
+            &summary.coins.to_string(),
+            position + Vec2::new(70.0, 36.0),
+            &style,
+            TEXT_LAYER,
+        );
+        sprite_renderer.render_text(
+            &format!("Day {}", summary.days + 1),
+            position + Vec2::new(-130.0, -40.0),
+            &TextStyle {
+                size: 28.0,
+                ..style
+            },
+            TEXT_LAYER,
+        );
+    }
+}
 
+impl Scene for TitleScene {
+    fn refresh(&mut self, data: &mut GameData) {
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            slot.summary = save::fetch(data.storage, i).map(|save| save.summary());
+        }
+    }
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
         if data.popr.distortion > 0.0 {
             data.popr.distortion -= data.popr.distortion * dt * 3.0;
             data.popr.time = 0.0
         }
         match &mut self.state {
             State::Idle => {
-                self.play_btn.update(context, data);
+                let mut chosen = None;
+                for (i, slot) in self.slots.iter_mut().enumerate() {
+                    if let Some(action) = slot.update(i, dt, data) {
+                        chosen = Some(action);
+                    }
+                }
 
-                if self.play_btn.is_clicked {
+                if let Some(action) = chosen {
                     self.state = State::PlayAnim {
                         play_anim_timer: 0.0,
                         vel_y: -300.0,
+                        action,
                     };
                 }
 
-                self.sound_toggle.update(context, data);
+                self.sound_toggle.update(dt, data);
 
                 if self.sound_toggle.is_clicked {
                     data.settings.sound_on = !data.settings.sound_on;
                 }
 
                 if data.settings.sound_on {
-                    self.sound_toggle.texture = ObjectSprite::Frame("title_sound_on");
-                    self.sound_toggle.hovered_texture = ObjectSprite::Frame("title_sound_on");
+                    self.sound_toggle.texture = ObjectSprite::Frame(SpriteId::TitleSoundOn);
+                    self.sound_toggle.hovered_texture = ObjectSprite::Frame(SpriteId::TitleSoundOn);
                 } else {
-                    self.sound_toggle.texture = ObjectSprite::Frame("title_sound_off");
-                    self.sound_toggle.hovered_texture = ObjectSprite::Frame("title_sound_off");
+                    self.sound_toggle.texture = ObjectSprite::Frame(SpriteId::TitleSoundOff);
+                    self.sound_toggle.hovered_texture =
+                        ObjectSprite::Frame(SpriteId::TitleSoundOff);
                 }
             }
             State::PlayAnim {
                 play_anim_timer,
                 vel_y,
+                action,
             } => {
                 if *play_anim_timer < 2.0 {
                     data.popr.darkness = *play_anim_timer / 2.0;
@@ -85,44 +213,50 @@ impl Scene for TitleScene {
                 } else if *play_anim_timer < 2.5 {
                     data.popr.darkness = 1.0;
                 } else {
-                    return Some(ObjectAction::Goto(super::ActiveScene::Garden));
+                    return Some(*action);
                 }
                 *play_anim_timer += dt;
 
                 self.bg_y += *vel_y * dt;
             }
-            State::Intro(time) => {
+            State::Intro(slide) => {
                 if data.popr.darkness > 0.0 {
                     data.popr.darkness -= dt * 3.0;
                 }
-                if *time < 0.0 {
-                    self.bg_y = -(RESOLUTION_Y as f32);
-                    if data.input.left_pressed {
-                        *time = 0.0
+                match slide {
+                    None => {
+                        self.bg_y = -(RESOLUTION_Y as f32);
+                        if data.input.left_pressed {
+                            *slide = Some(
+                                Tween::new(-(RESOLUTION_Y as f32), 0.0, 3.0)
+                                    .with_easing(Easing::QuadOut),
+                            );
+                        }
                     }
-                } else if *time < 3.0 {
-                    *time += dt;
-                    let t = 1.0 - (*time / 3.0);
-                    self.bg_y = -t * t * RESOLUTION_Y as f32;
-                } else {
-                    self.state = State::Idle;
+                    Some(tween) if !tween.is_finished() => {
+                        tween.update(dt);
+                        self.bg_y = tween.value();
+                    }
+                    Some(_) => self.state = State::Idle,
                 }
             }
         }
 
-        self.play_btn.position.y = self.bg_y - 20.0;
+        for slot in self.slots.iter_mut() {
+            slot.set_y(self.bg_y - 30.0);
+        }
         self.sound_toggle.position.y = self.bg_y - 170.0;
 
         None
     }
 
-    fn render(&mut self, _data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
+    fn render(&mut self, data: &mut GameData, sprite_renderer: &mut SpriteRenderer) {
         sprite_renderer.render(
             D2Instance {
                 position: Vec2::new(0.0, self.bg_y + RESOLUTION_Y as f32),
                 ..Default::default()
             },
-            "mainplant_hands",
+            SpriteId::MainplantHands,
             0,
         );
 
@@ -131,7 +265,7 @@ impl Scene for TitleScene {
                 position: Vec2::new(0.0, self.bg_y),
                 ..Default::default()
             },
-            "title_bg",
+            SpriteId::TitleBg,
             0,
         );
 
@@ -140,11 +274,27 @@ impl Scene for TitleScene {
                 position: Vec2::new(-11.0, self.bg_y + 120.0),
                 ..Default::default()
             },
-            "title_title",
+            SpriteId::TitleTitle,
             0,
         );
 
-        self.play_btn.render(sprite_renderer);
+        for slot in self.slots.iter() {
+            slot.render(sprite_renderer);
+        }
         self.sound_toggle.render(sprite_renderer);
+
+        let progress = data.audio.load_progress();
+        if progress < 1.0 {
+            sprite_renderer.render_text(
+                &format!("loading sounds {}%", (progress * 100.0) as u32),
+                Vec2::new(0.0, self.sound_toggle.position.y - 50.0),
+                &TextStyle {
+                    size: 24.0,
+                    align: TextAlign::Center,
+                    ..Default::default()
+                },
+                TEXT_LAYER,
+            );
+        }
     }
 }
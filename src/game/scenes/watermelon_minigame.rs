@@ -1,14 +1,17 @@
 use crate::game::constants::{RESOLUTION_X, RESOLUTION_Y};
+use crate::game::particles::ParticleEmitter;
+use crate::game::sprite_manifest::SpriteId;
 use crate::game::GameData;
 use crate::game::{clickableobject::ObjectAction, sprite_renderer::SpriteRenderer};
 use jandering_engine::types::DEG_TO_RAD;
-use jandering_engine::{engine::EngineContext, object::D2Instance, types::Vec2};
+use jandering_engine::{object::D2Instance, types::Vec2};
 use rand::Rng;
 
 use super::{MinigameFingers, Scene};
 
 struct FallingObject {
     pos: Vec2,
+    prev_pos: Vec2,
     velocity: Vec2,
     rotation: f32,
     collected: bool,
@@ -18,44 +21,45 @@ pub struct WatermelonMinigameScene {
     falling_objects: Vec<FallingObject>,
     time: f32,
     fingers: MinigameFingers,
+    sparkles: ParticleEmitter,
 }
 
 impl WatermelonMinigameScene {
-    pub fn new(_sprite_renderer: &mut SpriteRenderer) -> Self {
+    pub fn new() -> Self {
         let fingers = MinigameFingers {
             pos: Vec2::new(0.0, RESOLUTION_Y as f32 * -0.5 * 0.9),
+            prev_pos: Vec2::new(0.0, RESOLUTION_Y as f32 * -0.5 * 0.9),
             vel_x: 0.0,
         };
         Self {
             falling_objects: Vec::new(),
             time: 0.0,
             fingers,
+            sparkles: ParticleEmitter::new("coin_sparkle", Vec2::ZERO),
         }
     }
 }
 
 impl Scene for WatermelonMinigameScene {
-    fn refresh(&mut self, _data: &mut GameData, _sprite_renderer: &mut SpriteRenderer) {
+    fn refresh(&mut self, _data: &mut GameData) {
         self.falling_objects.clear();
+        self.sparkles.clear();
         self.fingers.pos.x = 0.0;
+        self.fingers.prev_pos = self.fingers.pos;
         self.time = 0.0;
         self.falling_objects.push(FallingObject {
             pos: Vec2::new(0.0, RESOLUTION_Y as f32 * 0.5 + 300.0),
+            prev_pos: Vec2::new(0.0, RESOLUTION_Y as f32 * 0.5 + 300.0),
             rotation: 0.0,
             velocity: Vec2::new(0.0, RESOLUTION_Y as f32 / -5.0),
             collected: false,
         })
     }
-    fn update(
-        &mut self,
-        context: &mut EngineContext,
-        _sprite_renderer: &mut SpriteRenderer,
-        data: &mut GameData,
-    ) -> Option<ObjectAction> {
-        let dt = context.dt as f32;
+    fn update(&mut self, dt: f32, data: &mut GameData) -> Option<ObjectAction> {
         self.time += dt;
 
-        self.fingers.update(data, context);
+        self.fingers.update(data, dt);
+        self.sparkles.update(dt);
 
         if self.falling_objects.len() == 1 {
             let object = &mut self.falling_objects[0];
@@ -73,6 +77,7 @@ impl Scene for WatermelonMinigameScene {
                     let pos = Vec2::new(0.0, pos_y);
                     self.falling_objects.push(FallingObject {
                         pos,
+                        prev_pos: pos,
                         rotation: 0.0,
                         velocity: dir * vel,
                         collected: false,
@@ -86,6 +91,7 @@ impl Scene for WatermelonMinigameScene {
                 } else {
                     if e.velocity.y.is_sign_negative() && self.fingers.collides(data, e.pos) {
                         e.collected = true;
+                        self.sparkles.burst_at(e.pos, data.effects_rng);
                         data.player.coins += 1;
                         e.velocity.y = 100.0;
                         e.velocity.x = 0.0;
@@ -104,9 +110,10 @@ impl Scene for WatermelonMinigameScene {
             return Some(ObjectAction::Goto(super::ActiveScene::Garden));
         }
 
-        self.falling_objects
-            .iter_mut()
-            .for_each(|e| e.pos += e.velocity * dt);
+        self.falling_objects.iter_mut().for_each(|e| {
+            e.prev_pos = e.pos;
+            e.pos += e.velocity * dt;
+        });
 
         None
     }
@@ -117,7 +124,7 @@ impl Scene for WatermelonMinigameScene {
                 scale: Vec2::new(RESOLUTION_X as f32, RESOLUTION_Y as f32),
                 ..Default::default()
             },
-            "garden_bg",
+            SpriteId::GardenBg,
             0,
         );
 
@@ -126,19 +133,21 @@ impl Scene for WatermelonMinigameScene {
         self.falling_objects.iter().for_each(|e| {
             sprite_renderer.render(
                 D2Instance {
-                    position: e.pos,
+                    position: e.prev_pos.lerp(e.pos, data.alpha),
                     rotation: e.rotation,
                     ..Default::default()
                 },
                 if e.collected {
-                    "market_coin"
+                    SpriteId::MarketCoin
                 } else if self.falling_objects.len() == 1 {
-                    "garden_falling_watermelon"
+                    SpriteId::GardenFallingWatermelon
                 } else {
-                    "garden_watermelon_piece"
+                    SpriteId::GardenWatermelonPiece
                 },
                 2,
             )
-        })
+        });
+
+        self.sparkles.render(sprite_renderer, data.alpha);
     }
 }
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{mixer::Bus, storage::Storage};
+
+pub const SETTINGS_VERSION: u32 = 2;
+
+const SETTINGS_KEY: &str = "gardenere_settings";
+
+// MIGRATIONS[i] upgrades settings from version i + 1 to version i + 2
+const MIGRATIONS: &[fn(&mut Value)] = &[split_ambience];
+
+// missing fields fall back to their defaults, so settings stored by older
+// versions keep loading when new fields get added
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub version: u32,
+    pub sound_on: bool,
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub ambience_volume: f32,
+    pub music_muted: bool,
+    pub sfx_muted: bool,
+    pub ambience_muted: bool,
+    pub max_distortion: Option<f32>,
+    pub reduced_motion: bool,
+    pub fullscreen: bool,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            sound_on: true,
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            ambience_volume: 1.0,
+            music_muted: false,
+            sfx_muted: false,
+            ambience_muted: false,
+            max_distortion: None,
+            reduced_motion: false,
+            fullscreen: false,
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn load(storage: &dyn Storage) -> Self {
+        let mut settings = match storage.read(SETTINGS_KEY) {
+            Some(text) => Self::from_json(&text).unwrap_or_else(|e| {
+                log::error!("failed loading settings: {}", e);
+                Self::default()
+            }),
+            None => Self::default(),
+        };
+        settings.validate();
+        settings
+    }
+
+    fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        let mut value: Value = serde_json::from_str(text)?;
+        migrate(&mut value);
+        serde_json::from_value(value)
+    }
+
+    pub fn store(&self, storage: &mut dyn Storage) {
+        let text = serde_json::to_string(self).expect("settings are always serializable");
+        if let Err(e) = storage.write(SETTINGS_KEY, &text) {
+            log::error!("failed writing settings: {:?}", e);
+        }
+    }
+
+    pub fn validate(&mut self) {
+        let defaults = Self::default();
+        self.master_volume = valid_volume(self.master_volume, defaults.master_volume);
+        self.music_volume = valid_volume(self.music_volume, defaults.music_volume);
+        self.sfx_volume = valid_volume(self.sfx_volume, defaults.sfx_volume);
+        self.ambience_volume = valid_volume(self.ambience_volume, defaults.ambience_volume);
+        self.max_distortion = self
+            .max_distortion
+            .filter(|val| val.is_finite())
+            .map(|val| val.max(0.0));
+    }
+
+    // sound_on and master_volume apply on top of every bus
+    pub fn bus_volume(&self, bus: Bus) -> f32 {
+        match bus {
+            Bus::Ambience => self.ambience_volume,
+            Bus::Music => self.music_volume,
+            Bus::Sfx => self.sfx_volume,
+        }
+    }
+
+    pub fn bus_muted(&self, bus: Bus) -> bool {
+        match bus {
+            Bus::Ambience => self.ambience_muted,
+            Bus::Music => self.music_muted,
+            Bus::Sfx => self.sfx_muted,
+        }
+    }
+
+    // browsers only allow entering fullscreen from a user gesture, so this
+    // has to be called while handling a click
+    #[cfg(target_arch = "wasm32")]
+    pub fn request_fullscreen(&self) {
+        if !self.fullscreen {
+            return;
+        }
+        if let Some(element) = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element())
+        {
+            let _ = element.request_fullscreen();
+        }
+    }
+
+    // on native the window belongs to the engine, it decides on fullscreen
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn request_fullscreen(&self) {}
+}
+
+// unlike saves, settings are never worth refusing. ones without a version count as the
+// first, ones from a newer build keep whatever fields still fit
+fn migrate(value: &mut Value) {
+    if !value.is_object() {
+        return;
+    }
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(1) as usize;
+
+    for migration in MIGRATIONS.iter().skip(version.saturating_sub(1)) {
+        migration(value);
+    }
+    value["version"] = Value::from(SETTINGS_VERSION);
+}
+
+// the distortion noise followed the sfx volume before it got a bus of its own
+fn split_ambience(value: &mut Value) {
+    if let Some(volume) = value.get("sfx_volume").cloned() {
+        value["ambience_volume"] = volume;
+    }
+}
+
+fn valid_volume(volume: f32, default: f32) -> f32 {
+    if volume.is_finite() {
+        volume.clamp(0.0, 1.0)
+    } else {
+        default
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::storage::MemoryStorage;
+
+    #[test]
+    fn settings_round_trip_through_storage() {
+        let mut storage = MemoryStorage::default();
+        let settings = GameSettings {
+            master_volume: 0.5,
+            music_muted: true,
+            max_distortion: Some(1.5),
+            reduced_motion: true,
+            ..Default::default()
+        };
+        settings.store(&mut storage);
+        assert_eq!(GameSettings::load(&storage), settings);
+    }
+
+    #[test]
+    fn version_1_noise_keeps_following_the_sfx_volume() {
+        let mut storage = MemoryStorage::default();
+        storage
+            .write(
+                SETTINGS_KEY,
+                r#"{"version":1,"sound_on":false,"music_volume":0.8,"sfx_volume":0.3}"#,
+            )
+            .unwrap();
+
+        let settings = GameSettings::load(&storage);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert!(!settings.sound_on);
+        assert_eq!(settings.music_volume, 0.8);
+        assert_eq!(settings.sfx_volume, 0.3);
+        assert_eq!(settings.ambience_volume, 0.3);
+    }
+
+    #[test]
+    fn current_settings_skip_the_migrations() {
+        let mut value: Value =
+            serde_json::from_str(r#"{"version":2,"sfx_volume":0.3,"ambience_volume":0.9}"#)
+                .unwrap();
+        migrate(&mut value);
+        assert_eq!(value["ambience_volume"], Value::from(0.9));
+    }
+
+    #[test]
+    fn broken_settings_fall_back_to_defaults() {
+        let mut storage = MemoryStorage::default();
+        storage.write(SETTINGS_KEY, "[1, 2]").unwrap();
+        assert_eq!(GameSettings::load(&storage), GameSettings::default());
+
+        storage
+            .write(SETTINGS_KEY, r#"{"version":1,"master_volume":7.0}"#)
+            .unwrap();
+        assert_eq!(GameSettings::load(&storage).master_volume, 1.0);
+    }
+}
@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+
+use jandering_engine::{object::D2Instance, types::Vec2};
+use rand::Rng;
+
+use super::{
+    audio::{Audio, AudioBackend},
+    camera::Camera,
+    clickableobject::ObjectAction,
+    constants::{REPLAY_STORE_TICKS, RESOLUTION_Y, STARTING_CASH, STARTING_POTS},
+    main_plant::MainPlant,
+    mixer::Bus,
+    music::{Music, MusicState},
+    particles::ParticleEmitter,
+    player::Player,
+    post_processing::PostProcessingState,
+    replay::Replay,
+    rng::{self, GameRng},
+    save::{self, SaveData},
+    scenes::{ActiveScene, Scenes},
+    settings::GameSettings,
+    sound_pool::SoundPool,
+    sprite_manifest::SpriteId,
+    sprite_renderer::SpriteRenderer,
+    storage::Storage,
+    GameData, InputInfo,
+};
+
+// the whole game minus the renderer, so it can be stepped headless
+pub struct Simulation {
+    pub scenes: Scenes,
+    pub player: Player,
+    pub main_plant: MainPlant,
+    pub popr: PostProcessingState,
+    pub settings: GameSettings,
+    // what's in storage, settings are written out whenever they stop matching it
+    stored_settings: GameSettings,
+    pub storage: Box<dyn Storage>,
+    pub input: InputInfo,
+    rng: GameRng,
+    effects_rng: GameRng,
+    audio: Audio,
+    seed: u64,
+    requested_seed: Option<u64>,
+    recording: Option<Replay>,
+    pending_action: Option<ObjectAction>,
+    refresh_scene: bool,
+    active_slot: usize,
+    music: Music,
+    leaves: ParticleEmitter,
+    leaf_sounds: SoundPool,
+    camera: Camera,
+}
+
+impl Simulation {
+    // the first new run uses seed, later ones draw theirs from the rng so a whole session
+    // stays reproducible. loading a slot doesn't use it up
+    pub fn new(
+        storage: Box<dyn Storage>,
+        audio: Box<dyn AudioBackend>,
+        seed: u64,
+        recording: Option<Replay>,
+    ) -> Self {
+        let settings = GameSettings::load(storage.as_ref());
+        let (scenes, player, main_plant) = make_everything();
+
+        Self {
+            scenes,
+            player,
+            main_plant,
+            popr: PostProcessingState::default(),
+            stored_settings: settings.clone(),
+            settings,
+            storage,
+            input: InputInfo::default(),
+            rng: rng::seeded(seed),
+            effects_rng: rng::effects(seed),
+            audio: Audio::new(audio),
+            seed,
+            requested_seed: Some(seed),
+            recording,
+            pending_action: None,
+            refresh_scene: true,
+            active_slot: 0,
+            music: Music::new(seed),
+            leaves: ParticleEmitter::new(
+                "falling_leaves",
+                Vec2::new(0.0, RESOLUTION_Y as f32 * 0.5 + 50.0),
+            ),
+            leaf_sounds: SoundPool::new("leaf"),
+            camera: Camera::default(),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(dt, &self.input);
+            if recording.frame_count() % REPLAY_STORE_TICKS == 0 {
+                recording.store(self.storage.as_mut());
+            }
+        }
+
+        if let Some(action) = self.pending_action.take() {
+            self.handle_action(action);
+        }
+
+        let view = self.camera.view(1.0);
+        self.input.mouse_pos = self
+            .input
+            .mouse_screen
+            .map(|screen| view.screen_to_world(screen));
+
+        let Self {
+            scenes,
+            player,
+            main_plant,
+            popr,
+            settings,
+            storage,
+            input,
+            rng,
+            effects_rng,
+            audio,
+            camera,
+            ..
+        } = self;
+
+        let mut data = GameData {
+            player,
+            main_plant,
+            input,
+            settings,
+            popr,
+            storage: storage.as_mut(),
+            rng,
+            effects_rng,
+            audio,
+            camera,
+            alpha: 1.0,
+        };
+
+        if self.refresh_scene {
+            scenes.get_active_scene().refresh(&mut data);
+            self.refresh_scene = false;
+        }
+
+        data.audio.apply_settings(data.settings);
+
+        let music_state = MusicState {
+            mood: scenes.active_scene.mood(),
+            stage: data.main_plant.stage,
+            distortion: data.popr.distortion,
+        };
+        for note in self.music.update(dt, &music_state) {
+            data.audio.play_variation(note.bus, note.sound);
+        }
+
+        self.pending_action = scenes.get_active_scene().update(dt, &mut data);
+        self.leaves.update(dt);
+        data.audio.update(dt);
+
+        if data.player.cut_eye {
+            data.popr.distortion = data.popr.distortion.max(3.0);
+        }
+
+        data.camera.update(dt, data.settings.reduced_motion);
+
+        data.popr.max_distortion = data.settings.max_distortion;
+        data.popr.reduced_motion = data.settings.reduced_motion;
+
+        data.input.clear_presses();
+
+        if self.settings != self.stored_settings {
+            self.settings.validate();
+            self.settings.store(self.storage.as_mut());
+            self.stored_settings = self.settings.clone();
+        }
+    }
+
+    // for when the game might not get another tick, like losing focus or closing
+    pub fn store_recording(&mut self) {
+        if let Some(recording) = &self.recording {
+            recording.store(self.storage.as_mut());
+        }
+    }
+
+    pub fn export_recording(&self) {
+        match &self.recording {
+            Some(recording) => recording.export(),
+            None => log::info!("not recording, start with the record option to export a replay"),
+        }
+    }
+
+    pub fn render(&mut self, sprite_renderer: &mut SpriteRenderer, alpha: f32) {
+        let Self {
+            scenes,
+            player,
+            main_plant,
+            popr,
+            settings,
+            storage,
+            input,
+            rng,
+            effects_rng,
+            audio,
+            camera,
+            ..
+        } = self;
+
+        let mut data = GameData {
+            player,
+            main_plant,
+            input,
+            settings,
+            popr,
+            storage: storage.as_mut(),
+            rng,
+            effects_rng,
+            audio,
+            camera,
+            alpha,
+        };
+
+        sprite_renderer.set_camera(data.camera.view(alpha));
+        scenes.get_active_scene().render(&mut data, sprite_renderer);
+        self.leaves.render(sprite_renderer, alpha);
+
+        if data.player.cut_eye {
+            sprite_renderer.render(D2Instance::default(), SpriteId::Noeye, 1000);
+        }
+    }
+
+    fn handle_action(&mut self, action: ObjectAction) {
+        let Self {
+            scenes,
+            player,
+            main_plant,
+            popr,
+            settings,
+            storage,
+            input,
+            rng,
+            effects_rng,
+            audio,
+            camera,
+            ..
+        } = self;
+
+        let mut data = GameData {
+            player,
+            main_plant,
+            input,
+            settings,
+            popr,
+            storage: storage.as_mut(),
+            rng,
+            effects_rng,
+            audio,
+            camera,
+            alpha: 1.0,
+        };
+
+        match action {
+            ObjectAction::Goto(scene) => {
+                play_leaf_sound(&mut self.leaf_sounds, &mut data);
+                self.leaves.burst(data.effects_rng);
+                data.camera.reset();
+                if matches!(scene, ActiveScene::Title) {
+                    save::clear(data.storage, self.active_slot);
+                    if let Some(recording) = &self.recording {
+                        recording.store(data.storage);
+                    }
+                    let (new_scenes, new_player, new_main_plant) = make_everything();
+                    *scenes = new_scenes;
+                    *data.player = new_player;
+                    *data.main_plant = new_main_plant;
+                }
+                scenes.set_scene(scene);
+                self.refresh_scene = true;
+            }
+            ObjectAction::NewGame(slot) => {
+                play_leaf_sound(&mut self.leaf_sounds, &mut data);
+                self.active_slot = slot;
+                save::clear(data.storage, slot);
+                self.seed = self.requested_seed.take().unwrap_or_else(|| data.rng.gen());
+                *data.rng = rng::seeded(self.seed);
+                *data.effects_rng = rng::effects(self.seed);
+                self.music = Music::new(self.seed);
+                log::info!("starting run with seed {}", self.seed);
+                scenes.set_scene(ActiveScene::Garden);
+                self.refresh_scene = true;
+            }
+            ObjectAction::LoadSlot(slot) => {
+                play_leaf_sound(&mut self.leaf_sounds, &mut data);
+                self.active_slot = slot;
+                // the run continues as saved, a requested seed waits for the next new game
+                match save::fetch(data.storage, slot) {
+                    Some(save) => {
+                        save.apply(data.player, data.main_plant, scenes, data.popr, data.rng);
+                        self.seed = save.seed;
+                        *data.effects_rng = rng::effects(self.seed);
+                        self.music = Music::new(self.seed);
+                    }
+                    None => scenes.set_scene(ActiveScene::Garden),
+                }
+                data.popr.darkness = 0.0;
+                self.refresh_scene = true;
+            }
+            ObjectAction::Exit => todo!(),
+            ObjectAction::NewDay => {
+                data.player.coins += 1;
+                data.player.total_coins += 1;
+                data.player.days += 1;
+                scenes.garden.new_day(&mut data);
+                (0..1).for_each(|_| data.main_plant.new_day(data.player, data.popr));
+                save::store(
+                    data.storage,
+                    self.active_slot,
+                    &SaveData::capture(
+                        data.player,
+                        data.main_plant,
+                        scenes,
+                        data.popr,
+                        self.seed,
+                        data.rng,
+                    ),
+                );
+                if let Some(recording) = &self.recording {
+                    recording.store(data.storage);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn make_everything() -> (Scenes, Player, MainPlant) {
+    let scenes = Scenes::new();
+
+    let player = Player {
+        hp: 100.0,
+        coins: STARTING_CASH,
+        total_coins: STARTING_CASH,
+        owned_seeds: HashMap::new(),
+        owned_pots: STARTING_POTS,
+        has_axe: false,
+        cut_finger: false,
+        used_finger: false,
+        cut_eye: false,
+        used_eye: false,
+        has_page: false,
+        days: 0,
+    };
+
+    let main_plant = MainPlant::new();
+
+    (scenes, player, main_plant)
+}
+
+fn play_leaf_sound(pool: &mut SoundPool, data: &mut GameData) {
+    let sound = pool.pick(data.effects_rng);
+    data.audio.play_variation(Bus::Sfx, sound);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        audio::NullAudio,
+        constants::{FLOWER_PRICE, TICK_LENGTH},
+        plant::PlantType,
+        replay::Playback,
+        storage::MemoryStorage,
+    };
+
+    const FLOWER_PACKET: Vec2 = Vec2::new(-100.0, 164.0);
+
+    fn simulation() -> Simulation {
+        let mut simulation = Simulation::new(
+            Box::<MemoryStorage>::default(),
+            Box::new(NullAudio),
+            7,
+            None,
+        );
+        simulation.handle_action(ObjectAction::NewGame(0));
+        simulation.update(TICK_LENGTH);
+        simulation
+    }
+
+    fn click(simulation: &mut Simulation, pos: Vec2) {
+        simulation.input.mouse_screen = Some(pos);
+        simulation.update(TICK_LENGTH);
+        simulation.input.left_pressed = true;
+        simulation.update(TICK_LENGTH);
+        simulation.input.left_released = true;
+        simulation.update(TICK_LENGTH);
+    }
+
+    #[test]
+    fn buying_seeds_spends_coins() {
+        let mut simulation = simulation();
+        simulation.handle_action(ObjectAction::Goto(ActiveScene::Market));
+        simulation.update(TICK_LENGTH);
+
+        click(&mut simulation, FLOWER_PACKET);
+        assert_eq!(simulation.player.coins, STARTING_CASH - FLOWER_PRICE);
+        assert_eq!(
+            simulation.player.owned_seeds.get(&PlantType::Flower),
+            Some(&1)
+        );
+
+        // can't afford a second one
+        click(&mut simulation, FLOWER_PACKET);
+        assert_eq!(simulation.player.coins, STARTING_CASH - FLOWER_PRICE);
+        assert_eq!(
+            simulation.player.owned_seeds.get(&PlantType::Flower),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn new_days_grow_the_plant_and_save_the_run() {
+        let mut simulation = simulation();
+        for _ in 0..3 {
+            simulation.handle_action(ObjectAction::NewDay);
+            simulation.update(TICK_LENGTH);
+        }
+
+        assert_eq!(simulation.player.days, 3);
+        assert_eq!(simulation.player.coins, STARTING_CASH + 3);
+        assert_eq!(simulation.main_plant.growth, 3);
+
+        let save = save::fetch(simulation.storage.as_ref(), 0).expect("new days store the run");
+        assert_eq!(save.player.days, 3);
+        assert_eq!(save.main_plant.growth, 3);
+    }
+
+    // the watermelon pieces scatter with the run rng, the fingers sweep under them
+    fn play_watermelon(simulation: &mut Simulation, mut input: impl FnMut(usize, &mut InputInfo)) {
+        simulation.handle_action(ObjectAction::NewGame(0));
+        simulation.handle_action(ObjectAction::Goto(ActiveScene::WatermelonMinigame));
+        for tick in 0..1500 {
+            input(tick, &mut simulation.input);
+            simulation.update(TICK_LENGTH);
+        }
+    }
+
+    fn sweep(tick: usize, input: &mut InputInfo) {
+        let x = (tick as f32 * 0.01).sin() * 500.0;
+        input.mouse_screen = Some(Vec2::new(x, -300.0));
+        input.left_pressed = tick % 90 == 0;
+        input.left_released = tick % 90 == 30;
+    }
+
+    fn recorded_simulation(seed: u64) -> Simulation {
+        let storage = MemoryStorage::default();
+        let recording = Replay::new(seed, &storage);
+        Simulation::new(
+            Box::new(storage),
+            Box::new(NullAudio),
+            seed,
+            Some(recording),
+        )
+    }
+
+    #[test]
+    fn same_seed_and_input_give_the_same_run() {
+        let run = || {
+            let mut simulation = recorded_simulation(11);
+            play_watermelon(&mut simulation, sweep);
+            assert_ne!(
+                simulation.rng,
+                rng::seeded(11),
+                "the minigame should draw from the run rng"
+            );
+            simulation
+        };
+        let mut first = run();
+        let second = run();
+        let player = serde_json::to_string(&first.player).unwrap();
+        assert_eq!(player, serde_json::to_string(&second.player).unwrap());
+        assert_eq!(first.rng, second.rng);
+
+        // playing the recording back lands on the same run and records the same tracks
+        let recording = first.recording.take().unwrap();
+        let frames = recording.frames();
+        let mut playback = Playback::new(recording);
+        let mut replayed = recorded_simulation(11);
+        play_watermelon(&mut replayed, |_, input| {
+            assert_eq!(playback.next(input), Some(TICK_LENGTH));
+        });
+        assert_eq!(player, serde_json::to_string(&replayed.player).unwrap());
+        assert_eq!(first.rng, replayed.rng);
+        assert_eq!(frames, replayed.recording.unwrap().frames());
+    }
+
+    #[test]
+    fn loading_keeps_the_saved_seed_and_leaves_the_requested_one_for_new_games() {
+        let mut saved = simulation();
+        saved.handle_action(ObjectAction::NewDay);
+        let text = saved.storage.read(&save::slot_key(0)).unwrap();
+        let mut storage = MemoryStorage::default();
+        storage.write(&save::slot_key(1), &text).unwrap();
+
+        let mut simulation = Simulation::new(Box::new(storage), Box::new(NullAudio), 99, None);
+        simulation.handle_action(ObjectAction::LoadSlot(1));
+        assert_eq!(simulation.seed, saved.seed);
+        assert_eq!(simulation.rng, saved.rng);
+        assert_eq!(simulation.effects_rng, rng::effects(saved.seed));
+
+        simulation.handle_action(ObjectAction::NewGame(0));
+        assert_eq!(simulation.seed, 99);
+        assert_eq!(simulation.rng, rng::seeded(99));
+    }
+
+    #[test]
+    fn particles_and_sounds_leave_the_run_rng_alone() {
+        let mut simulation = simulation();
+        let rng = simulation.rng.clone();
+
+        simulation.handle_action(ObjectAction::Goto(ActiveScene::Market));
+        simulation.input.mouse_screen = Some(FLOWER_PACKET);
+        for _ in 0..120 {
+            simulation.update(TICK_LENGTH);
+        }
+
+        assert_eq!(simulation.rng, rng);
+        assert_ne!(simulation.effects_rng, rng::effects(simulation.seed));
+    }
+
+    #[test]
+    fn settings_are_stored_when_they_change() {
+        let mut simulation = simulation();
+        simulation.settings.reduced_motion = true;
+        simulation.settings.sfx_volume = 0.25;
+        simulation.update(TICK_LENGTH);
+
+        let stored = GameSettings::load(simulation.storage.as_ref());
+        assert!(stored.reduced_motion);
+        assert_eq!(stored.sfx_volume, 0.25);
+    }
+}
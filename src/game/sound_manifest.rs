@@ -0,0 +1,6 @@
+// SoundId and the embedded files indexed by it are generated by build.rs from res/sounds
+include!(concat!(env!("OUT_DIR"), "/sounds.rs"));
+
+pub fn sound_bytes(sound: SoundId) -> &'static [u8] {
+    SOUND_FILES[sound as usize]
+}
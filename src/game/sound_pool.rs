@@ -0,0 +1,190 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::OnceLock,
+};
+
+use rand::Rng;
+use serde::Deserialize;
+
+use super::{rng::GameRng, sound_manifest::SoundId};
+
+#[derive(Debug, Deserialize)]
+pub struct PoolEntry {
+    pub sound: SoundId,
+    #[serde(default = "unit_weight")]
+    pub weight: f32,
+}
+
+// volume and pitch are [min, max] picked from uniformly, pitch is a playback rate.
+// the last avoid_repeat picks are left out of the next one
+#[derive(Debug, Deserialize)]
+pub struct PoolConfig {
+    pub sounds: Vec<PoolEntry>,
+    #[serde(default)]
+    pub avoid_repeat: usize,
+    #[serde(default = "unit_range")]
+    pub volume: [f32; 2],
+    #[serde(default = "unit_range")]
+    pub pitch: [f32; 2],
+}
+
+fn unit_weight() -> f32 {
+    1.0
+}
+
+fn unit_range() -> [f32; 2] {
+    [1.0, 1.0]
+}
+
+fn configs() -> &'static HashMap<String, PoolConfig> {
+    static CONFIGS: OnceLock<HashMap<String, PoolConfig>> = OnceLock::new();
+    CONFIGS.get_or_init(|| {
+        let configs: HashMap<String, PoolConfig> =
+            serde_json::from_str(include_str!("../../res/sound_pools.json"))
+                .unwrap_or_else(|e| panic!("failed parsing sound_pools.json: {}", e));
+        for (name, config) in configs.iter() {
+            if config.sounds.is_empty() || config.sounds.iter().any(|entry| entry.weight <= 0.0) {
+                panic!(
+                    "sound pool {} needs at least one sound and only positive weights",
+                    name
+                );
+            }
+        }
+        configs
+    })
+}
+
+pub fn pool_config(name: &str) -> &'static PoolConfig {
+    configs()
+        .get(name)
+        .unwrap_or_else(|| panic!("no sound pool named {}", name))
+}
+
+fn pick(range: [f32; 2], rng: &mut GameRng) -> f32 {
+    range[0] + (range[1] - range[0]) * rng.gen::<f32>()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SoundVariation {
+    pub sound: SoundId,
+    pub volume: f32,
+    pub pitch: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct SoundPool {
+    config: &'static PoolConfig,
+    recent: VecDeque<usize>,
+}
+
+impl SoundPool {
+    pub fn new(name: &str) -> Self {
+        Self {
+            config: pool_config(name),
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn pick(&mut self, rng: &mut GameRng) -> SoundVariation {
+        let sounds = &self.config.sounds;
+        let candidates: Vec<usize> = (0..sounds.len())
+            .filter(|i| !self.recent.contains(i))
+            .collect();
+        let total: f32 = candidates.iter().map(|&i| sounds[i].weight).sum();
+
+        let mut left = rng.gen::<f32>() * total;
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|&i| {
+                left -= sounds[i].weight;
+                left < 0.0
+            })
+            .unwrap_or(*candidates.last().unwrap());
+
+        // a window as big as the pool would leave nothing to pick from
+        self.recent.push_back(index);
+        if self.recent.len() > self.config.avoid_repeat.min(sounds.len() - 1) {
+            self.recent.pop_front();
+        }
+
+        SoundVariation {
+            sound: sounds[index].sound,
+            volume: pick(self.config.volume, rng),
+            pitch: pick(self.config.pitch, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng;
+
+    fn pool(weights: &[f32], avoid_repeat: usize) -> SoundPool {
+        let sounds = [SoundId::Note1, SoundId::Note2, SoundId::Note3];
+        let config = PoolConfig {
+            sounds: weights
+                .iter()
+                .zip(sounds)
+                .map(|(&weight, sound)| PoolEntry { sound, weight })
+                .collect(),
+            avoid_repeat,
+            volume: unit_range(),
+            pitch: unit_range(),
+        };
+        SoundPool {
+            config: Box::leak(Box::new(config)),
+            recent: VecDeque::new(),
+        }
+    }
+
+    #[test]
+    fn picks_follow_the_weights() {
+        let mut pool = pool(&[1.0, 3.0], 0);
+        let mut rng = rng::seeded(1);
+        let heavy = (0..4000)
+            .filter(|_| pool.pick(&mut rng).sound == SoundId::Note2)
+            .count();
+        assert!((2800..3200).contains(&heavy), "{}", heavy);
+    }
+
+    #[test]
+    fn recent_picks_are_left_out() {
+        let mut pool = pool(&[1.0, 1.0, 1.0], 1);
+        let mut rng = rng::seeded(2);
+        let mut last = pool.pick(&mut rng).sound;
+        for _ in 0..200 {
+            let sound = pool.pick(&mut rng).sound;
+            assert_ne!(sound, last);
+            last = sound;
+        }
+    }
+
+    #[test]
+    fn window_is_clamped_below_the_pool_size() {
+        let mut single = pool(&[1.0], 3);
+        let mut rng = rng::seeded(3);
+        for _ in 0..10 {
+            assert_eq!(single.pick(&mut rng).sound, SoundId::Note1);
+        }
+
+        // a window of the whole pool would cycle, one less still leaves a choice
+        let mut pair = pool(&[1.0, 1.0], 5);
+        let picks: Vec<SoundId> = (0..6).map(|_| pair.pick(&mut rng).sound).collect();
+        assert!(picks.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn configured_pools_leave_a_choice() {
+        for (name, config) in configs() {
+            if config.sounds.len() > 1 {
+                assert!(
+                    config.avoid_repeat < config.sounds.len() - 1,
+                    "{} would play its sounds in a fixed cycle",
+                    name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use jandering_engine::types::Vec2;
+
+// SpriteId and the tables indexed by it are generated by build.rs from every png under res/,
+// hovered::X is X's _hovered variant and only exists when that png does
+include!(concat!(env!("OUT_DIR"), "/sprites.rs"));
+
+pub fn sprite_size(sprite: SpriteId) -> Vec2 {
+    let (width, height) = SPRITE_SIZES[sprite as usize];
+    Vec2::new(width as f32, height as f32)
+}
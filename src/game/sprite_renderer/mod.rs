@@ -0,0 +1,624 @@
+use std::cmp::Ordering;
+
+use jandering_engine::{
+    bind_group::texture::TextureBindGroup,
+    engine::EngineContext,
+    object::{primitives, D2Instance, Object, VertexRaw},
+    renderer::{BindGroupHandle, Renderer},
+    shader::{create_shader, Shader, ShaderDescriptor},
+    texture::{Texture, TextureDescriptor},
+    types::Vec2,
+};
+
+use self::{bind_groups::SpriteCameraBindGroup, text::Font};
+
+use super::{
+    camera::CameraView,
+    constants::{RESOLUTION_X, RESOLUTION_Y},
+    sprite_manifest::{
+        SpriteId, ATLAS_PAGES, ATLAS_SIZE, SPRITE_ATLAS, SPRITE_SIZES, SPRITE_SLICES,
+    },
+};
+
+mod bind_groups;
+mod text;
+
+pub use text::{TextAlign, TextStyle};
+
+pub fn measure_text(text: &str, style: &TextStyle) -> Vec2 {
+    text::default_font().measure(text, style)
+}
+
+// D2Instance plus where in the atlas page the sprite sits
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpriteInstance {
+    pub position: [f32; 2],
+    pub scale: [f32; 2],
+    pub rotation: f32,
+    pub uv_offset: [f32; 2],
+    pub uv_scale: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl SpriteInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+        5 => Float32x2,
+        6 => Float32x2,
+        7 => Float32,
+        8 => Float32x2,
+        9 => Float32x2,
+        10 => Float32x4,
+    ];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+struct QueuedSprite {
+    instance: SpriteInstance,
+    page: usize,
+    z_index: u32,
+    order: usize,
+}
+
+impl QueuedSprite {
+    fn key(&self) -> DrawKey {
+        DrawKey {
+            z_index: self.z_index,
+            y: self.instance.position[1],
+            order: self.order,
+        }
+    }
+}
+
+// everything the draw order looks at, kept apart from the instance data so it can be tested
+#[derive(Clone, Copy, Debug)]
+struct DrawKey {
+    z_index: u32,
+    y: f32,
+    order: usize,
+}
+
+// lower layers first, then higher y first on y-sorted layers so things further up the
+// screen sit behind, then whatever was queued first. total_cmp keeps NaN positions
+// from breaking the sort
+fn draw_order(a: DrawKey, b: DrawKey, y_sorted: bool) -> Ordering {
+    a.z_index
+        .cmp(&b.z_index)
+        .then_with(|| {
+            if y_sorted {
+                b.y.total_cmp(&a.y)
+            } else {
+                Ordering::Equal
+            }
+        })
+        .then_with(|| a.order.cmp(&b.order))
+}
+
+// one part of a nine-slice, src is in pixels of the sprite and dst in pixels of the target,
+// both from their top left corner
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct SlicePiece {
+    src_position: Vec2,
+    src_size: Vec2,
+    dst_position: Vec2,
+    dst_size: Vec2,
+}
+
+// corners keep their size and edges stretch one way, unless the target is smaller than the
+// corners, then opposite corners shrink together and the empty middle pieces are left out
+fn nine_slice(sprite_size: Vec2, slice: [f32; 4], size: Vec2) -> Vec<SlicePiece> {
+    let [left, top, right, bottom] = slice;
+    let fit = |start: f32, end: f32, available: f32| {
+        let shrink = (available / (start + end)).min(1.0);
+        if shrink.is_finite() {
+            (start * shrink, end * shrink)
+        } else {
+            (0.0, 0.0)
+        }
+    };
+    let (dst_left, dst_right) = fit(left, right, size.x);
+    let (dst_top, dst_bottom) = fit(top, bottom, size.y);
+
+    let src_columns = [
+        (0.0, left),
+        (left, sprite_size.x - left - right),
+        (sprite_size.x - right, right),
+    ];
+    let src_rows = [
+        (0.0, top),
+        (top, sprite_size.y - top - bottom),
+        (sprite_size.y - bottom, bottom),
+    ];
+    let dst_columns = [
+        (0.0, dst_left),
+        (dst_left, size.x - dst_left - dst_right),
+        (size.x - dst_right, dst_right),
+    ];
+    let dst_rows = [
+        (0.0, dst_top),
+        (dst_top, size.y - dst_top - dst_bottom),
+        (size.y - dst_bottom, dst_bottom),
+    ];
+
+    let mut pieces = Vec::with_capacity(9);
+    for (&(src_y, src_height), &(dst_y, dst_height)) in src_rows.iter().zip(&dst_rows) {
+        for (&(src_x, src_width), &(dst_x, dst_width)) in src_columns.iter().zip(&dst_columns) {
+            if src_width <= 0.0 || src_height <= 0.0 || dst_width <= 0.0 || dst_height <= 0.0 {
+                continue;
+            }
+            pieces.push(SlicePiece {
+                src_position: Vec2::new(src_x, src_y),
+                src_size: Vec2::new(src_width, src_height),
+                dst_position: Vec2::new(dst_x, dst_y),
+                dst_size: Vec2::new(dst_width, dst_height),
+            });
+        }
+    }
+    pieces
+}
+
+const BATCH_SIZE: usize = 1024;
+// anything past this in a single frame is a runaway loop, not a busy scene
+const MAX_NUM_SPRITES: usize = 1 << 16;
+
+type Batch = Object<SpriteInstance>;
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct RenderStats {
+    pub queued: usize,
+    pub draw_calls: usize,
+    pub dropped: usize,
+    pub texture_switches: usize,
+}
+
+// colour is multiplied into the texture, so its alpha doubles as opacity
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SpriteEffects {
+    pub color: [f32; 4],
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Default for SpriteEffects {
+    fn default() -> Self {
+        Self {
+            color: [1.0, 1.0, 1.0, 1.0],
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+}
+
+impl SpriteEffects {
+    pub fn faded(alpha: f32) -> Self {
+        Self {
+            color: [1.0, 1.0, 1.0, alpha],
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Sprite {
+    pub page: usize,
+    pub size: Vec2,
+    pub uv_offset: Vec2,
+    pub uv_scale: Vec2,
+    // left, top, right, bottom
+    pub slice: Option<[f32; 4]>,
+}
+
+pub struct SpriteRenderer {
+    batches: Vec<Batch>,
+    queued: Vec<QueuedSprite>,
+    camera_bg: BindGroupHandle<SpriteCameraBindGroup>,
+    shader: Shader,
+
+    sprites: Vec<Sprite>,
+    pages: Vec<BindGroupHandle<TextureBindGroup>>,
+    font: &'static Font,
+    unsorted_layers: Vec<u32>,
+    camera: CameraView,
+
+    stats: RenderStats,
+}
+
+impl SpriteRenderer {
+    pub async fn new(renderer: &mut Renderer) -> Self {
+        let camera_bg = renderer.add_bind_group(SpriteCameraBindGroup::new(
+            renderer,
+            [RESOLUTION_X as f32, RESOLUTION_Y as f32],
+        ));
+
+        let pages: Vec<BindGroupHandle<TextureBindGroup>> = ATLAS_PAGES
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                let texture = Texture::from_bytes(renderer, bytes, TextureDescriptor::default())
+                    .unwrap_or_else(|_| panic!("failed loading atlas page {}", i));
+                let texture_handle = renderer.add_texture(texture);
+                let texture_bind_group = TextureBindGroup::new(renderer, texture_handle);
+                renderer.add_bind_group(texture_bind_group)
+            })
+            .collect();
+
+        let bind_groups = [camera_bg.into(), pages[0].into()];
+
+        let shader = create_shader(
+            renderer,
+            ShaderDescriptor {
+                code: include_str!("sprite_shader.wgsl"),
+                descriptors: &[VertexRaw::desc(), SpriteInstance::desc()],
+                bind_groups: &bind_groups,
+                targets: Some(&[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba16Float,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })]),
+                ..Default::default()
+            },
+        );
+
+        let sprites = SPRITE_SIZES
+            .iter()
+            .zip(SPRITE_ATLAS)
+            .zip(SPRITE_SLICES)
+            .map(|((&(width, height), &(page, x, y)), slice)| {
+                let size = Vec2::new(width as f32, height as f32);
+                Sprite {
+                    page,
+                    size,
+                    uv_offset: Vec2::new(x as f32, y as f32) / ATLAS_SIZE as f32,
+                    uv_scale: size / ATLAS_SIZE as f32,
+                    slice: slice.map(|insets| insets.map(|inset| inset as f32)),
+                }
+            })
+            .collect();
+
+        Self {
+            batches: Vec::new(),
+            queued: Vec::new(),
+            camera_bg,
+            shader,
+
+            sprites,
+            pages,
+            font: text::default_font(),
+            unsorted_layers: Vec::new(),
+            camera: CameraView::default(),
+
+            stats: RenderStats::default(),
+        }
+    }
+
+    // numbers for the last submitted frame
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    // used for everything submitted from here on
+    pub fn set_camera(&mut self, camera: CameraView) {
+        self.camera = camera;
+    }
+
+    // every layer is y-sorted unless opted out here, unsorted layers draw in queue order
+    pub fn set_layer_y_sort(&mut self, z_index: u32, y_sort: bool) {
+        self.unsorted_layers.retain(|layer| *layer != z_index);
+        if !y_sort {
+            self.unsorted_layers.push(z_index);
+        }
+    }
+
+    pub fn get_sprite(&mut self, sprite: SpriteId) -> &Sprite {
+        &self.sprites[sprite as usize]
+    }
+
+    pub fn render(&mut self, instance: D2Instance, sprite: SpriteId, z_index: u32) {
+        self.render_with_scale(instance, sprite, z_index, 1.0);
+    }
+
+    pub fn render_with_scale(
+        &mut self,
+        instance: D2Instance,
+        sprite: SpriteId,
+        z_index: u32,
+        scale: f32,
+    ) {
+        self.render_with_effects(instance, sprite, z_index, scale, SpriteEffects::default());
+    }
+
+    pub fn render_with_effects(
+        &mut self,
+        instance: D2Instance,
+        sprite: SpriteId,
+        z_index: u32,
+        scale: f32,
+        effects: SpriteEffects,
+    ) {
+        let sprite = &self.sprites[sprite as usize];
+
+        // flipping walks the atlas rect backwards instead of mirroring the quad
+        let mut uv_offset = sprite.uv_offset;
+        let mut uv_scale = sprite.uv_scale;
+        if effects.flip_x {
+            uv_offset.x += uv_scale.x;
+            uv_scale.x = -uv_scale.x;
+        }
+        if effects.flip_y {
+            uv_offset.y += uv_scale.y;
+            uv_scale.y = -uv_scale.y;
+        }
+
+        let page = sprite.page;
+        let instance = SpriteInstance {
+            position: instance.position.to_array(),
+            scale: (sprite.size * scale).to_array(),
+            rotation: instance.rotation,
+            uv_offset: uv_offset.to_array(),
+            uv_scale: uv_scale.to_array(),
+            color: effects.color,
+        };
+        self.queue(instance, page, z_index);
+    }
+
+    // corners keep their size, edges stretch along their side and the middle fills the rest.
+    // borders shrink evenly when size is smaller than them, flips are ignored
+    pub fn render_nine_slice(
+        &mut self,
+        instance: D2Instance,
+        sprite: SpriteId,
+        z_index: u32,
+        size: Vec2,
+        effects: SpriteEffects,
+    ) {
+        let id = sprite;
+        let sprite = &self.sprites[id as usize];
+        let slice = sprite.slice.unwrap_or_else(|| {
+            log::warn!("{:?} has no nine-slice insets, stretching it", id);
+            [0.0; 4]
+        });
+        let page = sprite.page;
+        let pixel_to_uv = sprite.uv_scale / sprite.size;
+        let uv_origin = sprite.uv_offset;
+        let rotation = Vec2::from_angle(instance.rotation);
+        let pieces: Vec<SpriteInstance> = nine_slice(sprite.size, slice, size)
+            .into_iter()
+            .map(|piece| {
+                let local = Vec2::new(
+                    piece.dst_position.x + piece.dst_size.x * 0.5 - size.x * 0.5,
+                    size.y * 0.5 - piece.dst_position.y - piece.dst_size.y * 0.5,
+                );
+                SpriteInstance {
+                    position: (instance.position + rotation.rotate(local)).to_array(),
+                    scale: piece.dst_size.to_array(),
+                    rotation: instance.rotation,
+                    uv_offset: (uv_origin + piece.src_position * pixel_to_uv).to_array(),
+                    uv_scale: (piece.src_size * pixel_to_uv).to_array(),
+                    color: effects.color,
+                }
+            })
+            .collect();
+
+        for piece in pieces {
+            self.queue(piece, page, z_index);
+        }
+    }
+
+    fn queue(&mut self, instance: SpriteInstance, page: usize, z_index: u32) {
+        self.queued.push(QueuedSprite {
+            instance,
+            page,
+            z_index,
+            order: self.queued.len(),
+        });
+    }
+
+    pub fn measure_text(&self, text: &str, style: &TextStyle) -> Vec2 {
+        self.font.measure(text, style)
+    }
+
+    pub fn render_text(&mut self, text: &str, position: Vec2, style: &TextStyle, z_index: u32) {
+        let sprite = &self.sprites[self.font.sprite as usize];
+        let pixel_to_uv = sprite.uv_scale / sprite.size;
+        let page = sprite.page;
+        let glyphs: Vec<SpriteInstance> = self
+            .font
+            .layout(text, style)
+            .into_iter()
+            .map(|placed| SpriteInstance {
+                position: (position + placed.center).to_array(),
+                scale: placed.size.to_array(),
+                rotation: 0.0,
+                uv_offset: (sprite.uv_offset + placed.glyph.rect_position * pixel_to_uv).to_array(),
+                uv_scale: (placed.glyph.rect_size * pixel_to_uv).to_array(),
+                color: style.color,
+            })
+            .collect();
+        for glyph in glyphs {
+            self.queue(glyph, page, z_index);
+        }
+    }
+
+    pub fn submit(&mut self, context: &mut EngineContext, renderer: &mut Renderer) {
+        let camera = renderer.get_bind_group_t_mut(self.camera_bg).unwrap();
+        camera.uniform.position = self.camera.position.to_array();
+        camera.uniform.zoom = self.camera.zoom;
+        camera.uniform.rotation = self.camera.rotation;
+
+        let unsorted_layers = &self.unsorted_layers;
+        self.queued.sort_unstable_by(|a, b| {
+            draw_order(a.key(), b.key(), !unsorted_layers.contains(&a.z_index))
+        });
+
+        let mut stats = RenderStats {
+            queued: self.queued.len(),
+            dropped: self.queued.len().saturating_sub(MAX_NUM_SPRITES),
+            ..Default::default()
+        };
+        if stats.dropped > 0 && self.stats.dropped == 0 {
+            log::warn!(
+                "{} sprites queued in one frame, dropping {}",
+                stats.queued,
+                stats.dropped
+            );
+        }
+        self.queued.truncate(MAX_NUM_SPRITES);
+
+        // consecutive sprites on the same layer and atlas page share one instanced draw,
+        // batches are only ever added so a busy frame's buffers get reused afterwards
+        let mut last_page = None;
+        for run in self
+            .queued
+            .chunk_by(|a, b| a.z_index == b.z_index && a.page == b.page)
+        {
+            for chunk in run.chunks(BATCH_SIZE) {
+                if stats.draw_calls == self.batches.len() {
+                    self.batches.push(primitives::quad(
+                        renderer,
+                        vec![SpriteInstance::default(); BATCH_SIZE],
+                    ));
+                }
+                let batch = &mut self.batches[stats.draw_calls];
+                batch.instances.clear();
+                batch
+                    .instances
+                    .extend(chunk.iter().map(|queued| queued.instance));
+                batch.update(context, renderer);
+
+                let page = chunk[0].page;
+                renderer.render(
+                    &[batch],
+                    context,
+                    &self.shader,
+                    &[self.camera_bg.into(), self.pages[page].into()],
+                );
+
+                stats.draw_calls += 1;
+                if last_page.is_some_and(|last| last != page) {
+                    stats.texture_switches += 1;
+                }
+                last_page = Some(page);
+            }
+        }
+
+        self.stats = stats;
+        self.queued.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(z_index: u32, y: f32, order: usize) -> DrawKey {
+        DrawKey { z_index, y, order }
+    }
+
+    fn sorted(mut keys: Vec<DrawKey>, y_sorted: bool) -> Vec<usize> {
+        keys.sort_unstable_by(|a, b| draw_order(*a, *b, y_sorted));
+        keys.into_iter().map(|key| key.order).collect()
+    }
+
+    #[test]
+    fn layers_come_before_everything_else() {
+        let keys = vec![key(2, -100.0, 0), key(1, 100.0, 1), key(0, 0.0, 2)];
+        assert_eq!(sorted(keys.clone(), true), [2, 1, 0]);
+        assert_eq!(sorted(keys, false), [2, 1, 0]);
+    }
+
+    #[test]
+    fn higher_y_draws_first_on_y_sorted_layers() {
+        let keys = vec![key(0, -10.0, 0), key(0, 30.0, 1), key(0, 10.0, 2)];
+        assert_eq!(sorted(keys.clone(), true), [1, 2, 0]);
+        assert_eq!(sorted(keys, false), [0, 1, 2]);
+    }
+
+    #[test]
+    fn equal_y_keeps_queue_order() {
+        let keys = (0..8).rev().map(|order| key(0, 5.0, order)).collect();
+        assert_eq!(sorted(keys, true), (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn nan_y_still_sorts_consistently() {
+        let keys = vec![
+            key(0, f32::NAN, 0),
+            key(0, 10.0, 1),
+            key(1, f32::NAN, 2),
+            key(0, -10.0, 3),
+        ];
+        let order = sorted(keys.clone(), true);
+        assert_eq!(order, sorted(keys.into_iter().rev().collect(), true));
+        // layers still win over a broken position
+        assert_eq!(order.last(), Some(&2));
+    }
+
+    fn piece(src: [f32; 4], dst: [f32; 4]) -> SlicePiece {
+        SlicePiece {
+            src_position: Vec2::new(src[0], src[1]),
+            src_size: Vec2::new(src[2], src[3]),
+            dst_position: Vec2::new(dst[0], dst[1]),
+            dst_size: Vec2::new(dst[2], dst[3]),
+        }
+    }
+
+    #[test]
+    fn nine_slice_keeps_corners_and_stretches_the_rest() {
+        let pieces = nine_slice(
+            Vec2::new(48.0, 40.0),
+            [16.0, 12.0, 8.0, 4.0],
+            Vec2::splat(100.0),
+        );
+        assert_eq!(pieces.len(), 9);
+        // top left corner, top edge, centre and bottom right corner
+        assert_eq!(
+            pieces[0],
+            piece([0.0, 0.0, 16.0, 12.0], [0.0, 0.0, 16.0, 12.0])
+        );
+        assert_eq!(
+            pieces[1],
+            piece([16.0, 0.0, 24.0, 12.0], [16.0, 0.0, 76.0, 12.0])
+        );
+        assert_eq!(
+            pieces[4],
+            piece([16.0, 12.0, 24.0, 24.0], [16.0, 12.0, 76.0, 84.0])
+        );
+        assert_eq!(
+            pieces[8],
+            piece([40.0, 36.0, 8.0, 4.0], [92.0, 96.0, 8.0, 4.0])
+        );
+        // left edge only stretches vertically
+        assert_eq!(
+            pieces[3],
+            piece([0.0, 12.0, 16.0, 24.0], [0.0, 12.0, 16.0, 84.0])
+        );
+    }
+
+    #[test]
+    fn nine_slice_shrinks_corners_that_dont_fit() {
+        let pieces = nine_slice(Vec2::splat(48.0), [16.0; 4], Vec2::new(16.0, 48.0));
+        // no room for the middle column, the corners share what width there is
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(
+            pieces[0],
+            piece([0.0, 0.0, 16.0, 16.0], [0.0, 0.0, 8.0, 16.0])
+        );
+        assert_eq!(
+            pieces[1],
+            piece([32.0, 0.0, 16.0, 16.0], [8.0, 0.0, 8.0, 16.0])
+        );
+        assert_eq!(
+            pieces[2],
+            piece([0.0, 16.0, 16.0, 16.0], [0.0, 16.0, 8.0, 16.0])
+        );
+        assert!(pieces
+            .iter()
+            .all(|piece| piece.dst_position.x + piece.dst_size.x <= 16.0));
+    }
+}
@@ -0,0 +1,65 @@
+struct Camera {
+    position: vec2<f32>,
+    resolution: vec2<f32>,
+    zoom: f32,
+    rotation: f32,
+    padding: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+@group(1) @binding(0)
+var tex: texture_2d<f32>;
+@group(1) @binding(1)
+var tex_sampler: sampler;
+
+struct InstanceInput{
+    @location(5) position: vec2<f32>,
+    @location(6) scale: vec2<f32>,
+    @location(7) rotation: f32,
+    @location(8) uv_offset: vec2<f32>,
+    @location(9) uv_scale: vec2<f32>,
+    @location(10) color: vec4<f32>,
+}
+
+struct VertexInput{
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct VertexOutput{
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput
+) -> VertexOutput{
+    var out: VertexOutput;
+
+    let local = model.position.xy * 0.5 * instance.scale;
+    let c = cos(instance.rotation);
+    let s = sin(instance.rotation);
+    let world = vec2<f32>(local.x * c - local.y * s, local.x * s + local.y * c) + instance.position;
+
+    // inverse of CameraView::screen_to_world
+    let relative = world - camera.position;
+    let cc = cos(camera.rotation);
+    let cs = sin(camera.rotation);
+    let view = vec2<f32>(relative.x * cc + relative.y * cs, -relative.x * cs + relative.y * cc) * camera.zoom;
+
+    out.clip_position = vec4<f32>(view / (camera.resolution * 0.5), 0.0, 1.0);
+    out.uv = instance.uv_offset + vec2<f32>(model.uv.x, 1.0 - model.uv.y) * instance.uv_scale;
+    out.color = instance.color;
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>{
+    return textureSample(tex, tex_sampler, in.uv) * in.color;
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use jandering_engine::types::Vec2;
+
+use crate::game::sprite_manifest::SpriteId;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+// position passed with the text is the top of the first line, x is the left edge, middle
+// or right edge depending on align. lines longer than max_width wrap at spaces
+#[derive(Clone, Copy, Debug)]
+pub struct TextStyle {
+    pub size: f32,
+    pub align: TextAlign,
+    pub max_width: Option<f32>,
+    pub color: [f32; 4],
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            size: 32.0,
+            align: TextAlign::Left,
+            max_width: None,
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+}
+
+// rect is in pixels of the font sprite, offset is from the pen position at the top of the line
+#[derive(Clone, Copy, Debug)]
+pub struct Glyph {
+    pub rect_position: Vec2,
+    pub rect_size: Vec2,
+    pub offset: Vec2,
+    pub advance: f32,
+}
+
+// center is relative to the text position, size is already scaled to the style
+pub struct PlacedGlyph {
+    pub glyph: Glyph,
+    pub center: Vec2,
+    pub size: Vec2,
+}
+
+// parsed once, so text can be measured without a renderer, e.g. to fit a button to its label
+pub fn default_font() -> &'static Font {
+    static FONT: OnceLock<Font> = OnceLock::new();
+    FONT.get_or_init(|| {
+        Font::parse(
+            SpriteId::FontsDefault,
+            include_str!("../../../res/fonts/default.fnt"),
+        )
+    })
+}
+
+pub struct Font {
+    pub sprite: SpriteId,
+    size: f32,
+    line_height: f32,
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl Font {
+    // reads the text flavour of the AngelCode BMFont format, only the info, common and
+    // char lines matter, kerning is ignored
+    pub fn parse(sprite: SpriteId, metrics: &str) -> Self {
+        let mut size = None;
+        let mut line_height = None;
+        let mut glyphs = HashMap::new();
+
+        for line in metrics.lines() {
+            let mut words = line.split_whitespace();
+            let tag = words.next();
+            let values: HashMap<&str, &str> =
+                words.filter_map(|word| word.split_once('=')).collect();
+            let get = |key: &str| -> f32 {
+                values
+                    .get(key)
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| panic!("font metrics line '{}' has no valid {}", line, key))
+            };
+
+            match tag {
+                Some("info") => size = Some(get("size").abs()),
+                Some("common") => line_height = Some(get("lineHeight")),
+                Some("char") => {
+                    let Some(c) = char::from_u32(get("id") as u32) else {
+                        continue;
+                    };
+                    glyphs.insert(
+                        c,
+                        Glyph {
+                            rect_position: Vec2::new(get("x"), get("y")),
+                            rect_size: Vec2::new(get("width"), get("height")),
+                            offset: Vec2::new(get("xoffset"), get("yoffset")),
+                            advance: get("xadvance"),
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let line_height = line_height.expect("font metrics have no common line");
+        Self {
+            sprite,
+            size: size.unwrap_or(line_height),
+            line_height,
+            glyphs,
+        }
+    }
+
+    fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    fn line_width(&self, line: &str, scale: f32) -> f32 {
+        line.chars()
+            .filter_map(|c| self.glyph(c))
+            .map(|glyph| glyph.advance * scale)
+            .sum()
+    }
+
+    fn wrap<'a>(&self, text: &'a str, style: &TextStyle) -> Vec<&'a str> {
+        let scale = style.size / self.size;
+        let mut lines = Vec::new();
+
+        for paragraph in text.split('\n') {
+            let Some(max_width) = style.max_width else {
+                lines.push(paragraph);
+                continue;
+            };
+
+            // a single word wider than max_width still gets its own line instead of splitting
+            let mut start = 0;
+            let mut end = 0;
+            for (i, _) in paragraph
+                .match_indices(' ')
+                .chain(std::iter::once((paragraph.len(), "")))
+            {
+                if end > start && self.line_width(&paragraph[start..i], scale) > max_width {
+                    lines.push(&paragraph[start..end]);
+                    start = end + 1;
+                }
+                end = i;
+            }
+            lines.push(&paragraph[start..]);
+        }
+
+        lines
+    }
+
+    pub fn measure(&self, text: &str, style: &TextStyle) -> Vec2 {
+        let scale = style.size / self.size;
+        let lines = self.wrap(text, style);
+        let width = lines
+            .iter()
+            .map(|line| self.line_width(line, scale))
+            .fold(0.0, f32::max);
+        Vec2::new(width, lines.len() as f32 * self.line_height * scale)
+    }
+
+    pub fn layout(&self, text: &str, style: &TextStyle) -> Vec<PlacedGlyph> {
+        let scale = style.size / self.size;
+        let mut placed = Vec::new();
+
+        for (i, line) in self.wrap(text, style).into_iter().enumerate() {
+            let mut pen = Vec2::new(
+                match style.align {
+                    TextAlign::Left => 0.0,
+                    TextAlign::Center => -self.line_width(line, scale) * 0.5,
+                    TextAlign::Right => -self.line_width(line, scale),
+                },
+                -(i as f32) * self.line_height * scale,
+            );
+
+            for c in line.chars() {
+                let Some(glyph) = self.glyph(c) else {
+                    continue;
+                };
+                if glyph.rect_size.x > 0.0 && glyph.rect_size.y > 0.0 {
+                    let size = glyph.rect_size * scale;
+                    let top_left = pen + Vec2::new(glyph.offset.x, -glyph.offset.y) * scale;
+                    placed.push(PlacedGlyph {
+                        glyph: *glyph,
+                        center: top_left + Vec2::new(size.x, -size.y) * 0.5,
+                        size,
+                    });
+                }
+                pen.x += glyph.advance * scale;
+            }
+        }
+
+        placed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a space, a and b are 5, 10 and 20 wide at size 10, kerning lines get skipped
+    const METRICS: &str = "info face=\"Test\" size=10
+common lineHeight=12 base=10
+char id=32 x=0 y=0 width=0 height=0 xoffset=0 yoffset=10 xadvance=5
+char id=63 x=0 y=0 width=8 height=10 xoffset=1 yoffset=0 xadvance=10
+char id=97 x=10 y=0 width=8 height=8 xoffset=1 yoffset=2 xadvance=10
+char id=98 x=20 y=0 width=18 height=10 xoffset=1 yoffset=0 xadvance=20
+kernings count=1
+kerning first=97 second=97 amount=-3";
+
+    fn font() -> Font {
+        Font::parse(SpriteId::FontsDefault, METRICS)
+    }
+
+    fn style(max_width: Option<f32>) -> TextStyle {
+        TextStyle {
+            size: 10.0,
+            max_width,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lines_wrap_at_spaces_before_max_width() {
+        let font = font();
+        assert_eq!(font.wrap("aa aa aa", &style(Some(45.0))), ["aa aa", "aa"]);
+        assert_eq!(
+            font.wrap("aa aa aa", &style(Some(44.0))),
+            ["aa", "aa", "aa"]
+        );
+        assert_eq!(font.wrap("aa aa aa", &style(None)), ["aa aa aa"]);
+    }
+
+    #[test]
+    fn newlines_always_break() {
+        let font = font();
+        assert_eq!(font.wrap("a\nb", &style(None)), ["a", "b"]);
+        assert_eq!(
+            font.wrap("aa aa\n\nb", &style(Some(30.0))),
+            ["aa", "aa", "", "b"]
+        );
+        assert_eq!(font.measure("a\nb", &style(None)), Vec2::new(20.0, 24.0));
+    }
+
+    #[test]
+    fn long_words_get_their_own_line() {
+        let font = font();
+        let wrapped = font.wrap("a bbbb a a", &style(Some(30.0)));
+        assert_eq!(wrapped, ["a", "bbbb", "a a"]);
+        assert_eq!(
+            font.measure("a bbbb a a", &style(Some(30.0))),
+            Vec2::new(80.0, 36.0)
+        );
+    }
+
+    #[test]
+    fn measure_scales_with_size_and_skips_kerning() {
+        let font = font();
+        let big = TextStyle {
+            size: 20.0,
+            ..style(None)
+        };
+        assert_eq!(font.measure("aa", &big), Vec2::new(40.0, 24.0));
+        // unknown characters take the place of a question mark
+        assert_eq!(font.measure("é", &style(None)).x, 10.0);
+    }
+
+    #[test]
+    fn layout_aligns_lines_and_skips_empty_glyphs() {
+        let font = font();
+        let centered = TextStyle {
+            align: TextAlign::Center,
+            ..style(None)
+        };
+        let placed = font.layout("a b\nb", &centered);
+        // the space has no rect, so only three glyphs get drawn
+        assert_eq!(placed.len(), 3);
+
+        // "a b" is 35 wide, a sits 1 in and 2 down from the pen
+        assert_eq!(placed[0].center, Vec2::new(-17.5 + 1.0 + 4.0, -2.0 - 4.0));
+        assert_eq!(placed[0].size, Vec2::new(8.0, 8.0));
+        // the second line starts a line height lower
+        assert_eq!(placed[2].center, Vec2::new(-10.0 + 1.0 + 9.0, -12.0 - 5.0));
+    }
+}
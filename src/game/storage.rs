@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Unavailable,
+    Io(std::io::Error),
+}
+
+pub trait Storage {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError>;
+    fn remove(&mut self, key: &str);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn default_storage() -> Box<dyn Storage> {
+    match LocalStorage::new() {
+        Some(storage) => Box::new(storage),
+        None => {
+            log::error!("localStorage unavailable, progress won't be kept");
+            Box::<MemoryStorage>::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_storage() -> Box<dyn Storage> {
+    match FileStorage::new() {
+        Some(storage) => Box::new(storage),
+        None => {
+            log::error!("no data directory, progress won't be kept");
+            Box::<MemoryStorage>::default()
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage {
+    storage: web_sys::Storage,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    pub fn new() -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        Some(Self { storage })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.storage.get_item(key).ok()?
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.storage
+            .set_item(key, value)
+            .map_err(|_| StorageError::Unavailable)
+    }
+
+    fn remove(&mut self, key: &str) {
+        let _ = self.storage.remove_item(key);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    pub fn new() -> Option<Self> {
+        Some(Self {
+            dir: dirs::data_dir()?.join("gardenere"),
+        })
+    }
+
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        std::fs::create_dir_all(&self.dir).map_err(StorageError::Io)?;
+        std::fs::write(self.path(key), value).map_err(StorageError::Io)
+    }
+
+    fn remove(&mut self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryStorage {
+    entries: HashMap<String, String>,
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.entries.get(key).cloned()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
@@ -0,0 +1,272 @@
+use jandering_engine::types::Vec2;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicOut,
+    BackOut,
+    PowIn(f32),
+}
+
+impl Easing {
+    // t goes from 0 to 1, the result starts at 0 and ends at 1
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) * 0.5
+                }
+            }
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                1.0 + (OVERSHOOT + 1.0) * (t - 1.0).powi(3) + OVERSHOOT * (t - 1.0).powi(2)
+            }
+            Easing::PowIn(power) => t.powf(power),
+        }
+    }
+}
+
+pub trait Tweenable: Copy {
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Vec2::lerp(self, to, t)
+    }
+}
+
+// times are in seconds of simulation time, so motion doesn't depend on the frame rate
+#[derive(Clone, Debug)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: f32,
+    delay: f32,
+    easing: Easing,
+    event: Option<&'static str>,
+    elapsed: f32,
+}
+
+impl<T: Tweenable> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            delay: 0.0,
+            easing: Easing::Linear,
+            event: None,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    // handed back from update on the step the tween finishes
+    pub fn with_event(mut self, event: &'static str) -> Self {
+        self.event = Some(event);
+        self
+    }
+
+    pub fn then(self, next: Tween<T>) -> Sequence<T> {
+        Sequence::from(self).then(next)
+    }
+
+    pub fn value_at(&self, time: f32) -> T {
+        let t = if self.duration > 0.0 {
+            (time - self.delay) / self.duration
+        } else if time >= self.delay {
+            1.0
+        } else {
+            0.0
+        };
+        self.from.lerp(self.to, self.easing.apply(t))
+    }
+
+    pub fn value(&self) -> T {
+        self.value_at(self.elapsed)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.delay + self.duration
+    }
+
+    // returns the time left over past the end, so sequences don't lose any
+    fn advance(&mut self, dt: f32) -> f32 {
+        let end = self.delay + self.duration;
+        let leftover = (self.elapsed + dt - end).max(0.0);
+        self.elapsed = (self.elapsed + dt).min(end);
+        leftover
+    }
+
+    pub fn update(&mut self, dt: f32) -> Option<&'static str> {
+        let was_finished = self.is_finished();
+        self.advance(dt);
+        if !was_finished && self.is_finished() {
+            self.event
+        } else {
+            None
+        }
+    }
+}
+
+// tweens played back to back, each one's events come out as it finishes
+#[derive(Clone, Debug)]
+pub struct Sequence<T> {
+    tweens: Vec<Tween<T>>,
+    current: usize,
+}
+
+impl<T: Tweenable> From<Tween<T>> for Sequence<T> {
+    fn from(tween: Tween<T>) -> Self {
+        Self {
+            tweens: vec![tween],
+            current: 0,
+        }
+    }
+}
+
+impl<T: Tweenable> Sequence<T> {
+    pub fn then(mut self, next: Tween<T>) -> Self {
+        self.tweens.push(next);
+        self
+    }
+
+    pub fn value(&self) -> T {
+        self.tweens[self.current].value()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current == self.tweens.len() - 1 && self.tweens[self.current].is_finished()
+    }
+
+    pub fn update(&mut self, dt: f32) -> Vec<&'static str> {
+        let mut events = Vec::new();
+        let mut dt = dt;
+        loop {
+            let tween = &mut self.tweens[self.current];
+            let was_finished = tween.is_finished();
+            dt = tween.advance(dt);
+            if !was_finished && tween.is_finished() {
+                events.extend(tween.event);
+            }
+            if !tween.is_finished() || self.current == self.tweens.len() - 1 {
+                break;
+            }
+            self.current += 1;
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: &[Easing] = &[
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicOut,
+        Easing::BackOut,
+        Easing::PowIn(3.0),
+    ];
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for &easing in EASINGS {
+            assert!(easing.apply(0.0).abs() < 1e-5, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-5, "{:?}", easing);
+            assert_eq!(easing.apply(-1.0), easing.apply(0.0), "{:?}", easing);
+            assert_eq!(easing.apply(2.0), easing.apply(1.0), "{:?}", easing);
+        }
+        assert!(Easing::BackOut.apply(0.7) > 1.0);
+    }
+
+    #[test]
+    fn tweens_clamp_to_their_ends() {
+        let mut tween = Tween::new(2.0, 6.0, 1.0).with_event("done");
+        assert_eq!(tween.value_at(-1.0), 2.0);
+        assert_eq!(tween.value_at(5.0), 6.0);
+
+        assert_eq!(tween.update(0.5), None);
+        assert_eq!(tween.value(), 4.0);
+        assert_eq!(tween.update(3.0), Some("done"));
+        assert_eq!(tween.value(), 6.0);
+        // the event only fires on the step that finishes it
+        assert_eq!(tween.update(1.0), None);
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn delays_hold_the_start_value() {
+        let mut tween = Tween::new(Vec2::ZERO, Vec2::new(4.0, 8.0), 2.0).with_delay(1.0);
+        tween.update(0.75);
+        assert_eq!(tween.value(), Vec2::ZERO);
+        tween.update(1.25);
+        assert_eq!(tween.value(), Vec2::new(2.0, 4.0));
+
+        let instant = Tween::new(0.0, 1.0, 0.0).with_delay(0.5);
+        assert_eq!(instant.value_at(0.25), 0.0);
+        assert_eq!(instant.value_at(0.5), 1.0);
+    }
+
+    #[test]
+    fn sequences_carry_leftover_time_and_events() {
+        let mut sequence = Tween::new(0.0, 1.0, 1.0)
+            .with_event("up")
+            .then(Tween::new(1.0, 0.0, 1.0).with_delay(0.5).with_event("down"));
+
+        assert!(sequence.update(0.5).is_empty());
+        assert_eq!(sequence.value(), 0.5);
+
+        // finishes the first tween and runs halfway through the second one's delay
+        assert_eq!(sequence.update(0.75), vec!["up"]);
+        assert_eq!(sequence.value(), 1.0);
+
+        assert!(sequence.update(0.75).is_empty());
+        assert_eq!(sequence.value(), 0.5);
+        assert!(!sequence.is_finished());
+
+        assert_eq!(sequence.update(10.0), vec!["down"]);
+        assert_eq!(sequence.value(), 0.0);
+        assert!(sequence.is_finished());
+        assert!(sequence.update(1.0).is_empty());
+    }
+
+    #[test]
+    fn one_long_step_fires_every_event() {
+        let mut sequence = Tween::new(0.0, 1.0, 0.1)
+            .with_event("a")
+            .then(Tween::new(1.0, 2.0, 0.1).with_event("b"))
+            .then(Tween::new(2.0, 3.0, 0.1).with_event("c"));
+        assert_eq!(sequence.update(1.0), vec!["a", "b", "c"]);
+        assert_eq!(sequence.value(), 3.0);
+    }
+}